@@ -7,8 +7,9 @@ use linkify::LinkFinder;
 
 #[export_name="rust_fuzzer_test_input"]
 pub extern "C" fn go(data: &[u8]) {
+    let finder = LinkFinder::new();
     if let Ok(s) = str::from_utf8(data) {
-        let finder = LinkFinder::new();
         finder.links(s).count();
     }
+    finder.links_bytes(data).count();
 }
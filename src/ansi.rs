@@ -0,0 +1,206 @@
+use std::mem;
+use std::ops::Range;
+
+use memchr::{memchr, memrchr};
+
+use crate::finder::Segment;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+/// Split text with ANSI escape sequences into segments, in order. Escape sequences such as colors
+/// (`\x1b[31m`) are skipped, so links are found in the text as it's displayed. The text of
+/// existing OSC 8 hyperlinks is not scanned.
+pub(crate) fn segments(text: &str) -> Vec<Segment> {
+    let bytes = text.as_bytes();
+    let mut segments = Vec::new();
+    let mut mapped = Mapped::new();
+    let mut i = 0;
+    while let Some(esc) = memchr(ESC, &bytes[i..]).map(|esc| i + esc) {
+        mapped.push(text, i..esc);
+        let sequence = match sequence(bytes, esc) {
+            Some(sequence) => sequence,
+            // Not a complete escape sequence, skip the escape character only
+            None => Sequence {
+                end: esc + 1,
+                hyperlink: None,
+            },
+        };
+        i = sequence.end;
+        if sequence.hyperlink == Some(true) {
+            // Skip to the end of the hyperlink
+            mem::replace(&mut mapped, Mapped::new()).finish(&mut segments);
+            i = hyperlink_end(bytes, i);
+        }
+    }
+    mapped.push(text, i..text.len());
+    mapped.finish(&mut segments);
+    segments
+}
+
+/// The end of a link found in text with escape sequences, without the escape sequences at the end
+/// of it (e.g. `\x1b[0m` after a URL).
+pub(crate) fn trim_end(text: &str, start: usize, mut end: usize) -> usize {
+    let bytes = text.as_bytes();
+    while let Some(esc) = memrchr(ESC, &bytes[start..end]).map(|esc| start + esc) {
+        match sequence(bytes, esc) {
+            Some(sequence) if sequence.end == end => end = esc,
+            _ => break,
+        }
+    }
+    end
+}
+
+/// Remove the escape sequences from the text.
+pub(crate) fn strip(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut stripped = String::with_capacity(text.len());
+    let mut i = 0;
+    while let Some(esc) = memchr(ESC, &bytes[i..]).map(|esc| i + esc) {
+        stripped.push_str(&text[i..esc]);
+        i = sequence(bytes, esc).map_or(esc + 1, |sequence| sequence.end);
+    }
+    stripped.push_str(&text[i..]);
+    stripped
+}
+
+/// An escape sequence, with whether it starts (`true`) or ends (`false`) an OSC 8 hyperlink.
+struct Sequence {
+    end: usize,
+    hyperlink: Option<bool>,
+}
+
+/// Parse the escape sequence at the index (of the escape character).
+fn sequence(bytes: &[u8], esc: usize) -> Option<Sequence> {
+    match *bytes.get(esc + 1)? {
+        // CSI (Control Sequence Introducer) like SGR (Select Graphic Rendition) `\x1b[1;31m`:
+        // parameter bytes, intermediate bytes and a final byte
+        b'[' => {
+            let mut i = esc + 2;
+            while matches!(bytes.get(i), Some(0x20..=0x3f)) {
+                i += 1;
+            }
+            match bytes.get(i) {
+                Some(0x40..=0x7e) => Some(Sequence {
+                    end: i + 1,
+                    hyperlink: None,
+                }),
+                _ => None,
+            }
+        }
+        // OSC (Operating System Command) like `\x1b]8;;http://example.org\x1b\\`, terminated by
+        // ST (`\x1b\\`) or BEL. Without a terminator, the rest of the input is part of it (like
+        // terminals wait for the terminator), which also avoids scanning the input again for
+        // each unterminated sequence.
+        b']' => {
+            let start = esc + 2;
+            let mut i = start;
+            loop {
+                match bytes.get(i) {
+                    Some(&BEL) => break,
+                    Some(&ESC) if bytes.get(i + 1) == Some(&b'\\') => break,
+                    Some(_) => i += 1,
+                    None => {
+                        return Some(Sequence {
+                            end: bytes.len(),
+                            hyperlink: None,
+                        })
+                    }
+                }
+            }
+            let end = if bytes[i] == BEL { i + 1 } else { i + 2 };
+            let hyperlink = osc_hyperlink(&bytes[start..i]);
+            Some(Sequence { end, hyperlink })
+        }
+        // Other sequences with two characters like `\x1b(B`, or `\x1bc`
+        b'(' | b')' => match bytes.get(esc + 2) {
+            Some(0x20..=0x7e) => Some(Sequence {
+                end: esc + 3,
+                hyperlink: None,
+            }),
+            _ => None,
+        },
+        0x40..=0x7e => Some(Sequence {
+            end: esc + 2,
+            hyperlink: None,
+        }),
+        _ => None,
+    }
+}
+
+/// For the content of an OSC 8 sequence (`8;params;URI`), whether it starts a hyperlink (a
+/// non-empty URI) or ends it.
+fn osc_hyperlink(content: &[u8]) -> Option<bool> {
+    if !content.starts_with(b"8;") {
+        return None;
+    }
+    let params_end = memchr(b';', &content[2..])?;
+    Some(content.len() > 2 + params_end + 1)
+}
+
+/// The index after the OSC 8 sequence that ends the hyperlink starting at the index.
+fn hyperlink_end(bytes: &[u8], mut i: usize) -> usize {
+    while let Some(esc) = memchr(ESC, &bytes[i..]).map(|esc| i + esc) {
+        match sequence(bytes, esc) {
+            Some(Sequence {
+                end,
+                hyperlink: Some(false),
+            }) => return end,
+            Some(sequence) => i = sequence.end,
+            None => i = esc + 1,
+        }
+    }
+    bytes.len()
+}
+
+/// The text without escape sequences and the offsets for a `Segment::Mapped`.
+struct Mapped {
+    text: String,
+    offsets: Vec<usize>,
+    start: usize,
+    end: usize,
+    /// Whether escape sequences were skipped, otherwise it's a `Segment::Text`
+    skipped: bool,
+}
+
+impl Mapped {
+    fn new() -> Mapped {
+        Mapped {
+            text: String::new(),
+            offsets: Vec::new(),
+            start: 0,
+            end: 0,
+            skipped: false,
+        }
+    }
+
+    fn push(&mut self, text: &str, range: Range<usize>) {
+        if range.start >= range.end {
+            return;
+        }
+        if self.text.is_empty() {
+            self.start = range.start;
+        } else if self.end != range.start {
+            self.skipped = true;
+        }
+        self.end = range.end;
+        self.text.push_str(&text[range.clone()]);
+        self.offsets.extend(range);
+    }
+
+    fn finish(self, segments: &mut Vec<Segment>) {
+        if self.text.is_empty() {
+            return;
+        }
+        if self.skipped {
+            let mut offsets = self.offsets;
+            offsets.push(self.end);
+            segments.push(Segment::Mapped {
+                text: self.text,
+                offsets,
+            });
+        } else {
+            segments.push(Segment::Text(self.start..self.end));
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! The `linkify-lsp` language server that finds links in documents with linkify.
+//!
+//! It talks the Language Server Protocol over standard input and output and answers
+//! `textDocument/documentLink` requests. The documents are synced in full. The options of the
+//! finder are the `LinkFinderOptions` from the `initializationOptions` or from the `linkify`
+//! section of the settings in `workspace/didChangeConfiguration`, e.g.:
+//!
+//! ```json
+//! { "kinds": ["url", "email"], "url_must_have_scheme": false }
+//! ```
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+use linkify::html;
+use linkify::{LineIndex, Link, LinkFinder, LinkFinderOptions, LinkKind, Position};
+use serde_json::{json, Value};
+
+// Error codes from JSON-RPC and LSP
+const PARSE_ERROR: i64 = -32700;
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+const SERVER_NOT_INITIALIZED: i64 = -32002;
+const INVALID_REQUEST: i64 = -32600;
+
+/// `TextDocumentSyncKind.Full`
+const SYNC_FULL: u32 = 1;
+
+/// The maximum `Content-Length` of a message, so that a bad header can't make the server allocate
+/// arbitrary amounts of memory.
+const MAX_CONTENT_LENGTH: u64 = 64 * 1024 * 1024;
+
+/// The maximum length of a header line, for the same reason.
+const MAX_HEADER_LEN: u64 = 8 * 1024;
+
+struct Server {
+    finder: LinkFinder,
+    documents: HashMap<String, String>,
+    initialized: bool,
+    shutdown: bool,
+}
+
+/// An error response for a request.
+struct Error {
+    code: i64,
+    message: String,
+}
+
+impl Error {
+    fn new(code: i64, message: impl Into<String>) -> Error {
+        Error {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+fn main() {
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+    let mut server = Server {
+        finder: LinkFinder::new(),
+        documents: HashMap::new(),
+        initialized: false,
+        shutdown: false,
+    };
+
+    loop {
+        let message = match read_message(&mut input) {
+            Ok(Some(Ok(message))) => message,
+            Ok(Some(Err(error))) => {
+                send(&mut output, &error_response(Value::Null, error));
+                continue;
+            }
+            // The client went away without `exit`
+            Ok(None) => process::exit(1),
+            Err(e) => {
+                eprintln!("linkify-lsp: {}", e);
+                process::exit(1);
+            }
+        };
+        let message: Value = match serde_json::from_str(&message) {
+            Ok(message) => message,
+            Err(e) => {
+                let response = error_response(Value::Null, Error::new(PARSE_ERROR, e.to_string()));
+                send(&mut output, &response);
+                continue;
+            }
+        };
+
+        let method = message.get("method").and_then(Value::as_str);
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        match (message.get("id").cloned(), method) {
+            // Request
+            (Some(id), Some(method)) => {
+                let response = match server.request(method, params) {
+                    Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                    Err(error) => error_response(id, error),
+                };
+                send(&mut output, &response);
+            }
+            // Notification
+            (None, Some("exit")) => process::exit(if server.shutdown { 0 } else { 1 }),
+            (None, Some(method)) => server.notification(method, params),
+            // Responses to requests of the server (there are none) or invalid messages
+            (Some(_), None) => {}
+            (None, None) => {
+                let error = Error::new(INVALID_REQUEST, "message without method");
+                send(&mut output, &error_response(Value::Null, error));
+            }
+        }
+    }
+}
+
+impl Server {
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, Error> {
+        if !self.initialized && method != "initialize" {
+            return Err(Error::new(SERVER_NOT_INITIALIZED, "server not initialized"));
+        }
+        if self.shutdown {
+            return Err(Error::new(INVALID_REQUEST, "server is shut down"));
+        }
+        match method {
+            "initialize" => {
+                if let Some(options) = params.get("initializationOptions") {
+                    self.configure(options);
+                }
+                self.initialized = true;
+                Ok(json!({
+                    "capabilities": {
+                        "textDocumentSync": SYNC_FULL,
+                        "documentLinkProvider": {"resolveProvider": false},
+                    },
+                    "serverInfo": {"name": "linkify-lsp", "version": env!("CARGO_PKG_VERSION")},
+                }))
+            }
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/documentLink" => {
+                let uri = uri(&params)
+                    .ok_or_else(|| Error::new(INVALID_PARAMS, "missing textDocument.uri"))?;
+                let links = match self.documents.get(uri) {
+                    Some(text) => document_links(&self.finder, text),
+                    None => Vec::new(),
+                };
+                Ok(Value::Array(links))
+            }
+            _ => Err(Error::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method {}", method),
+            )),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: Value) {
+        if self.shutdown {
+            return;
+        }
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str();
+                if let (Some(uri), Some(text)) = (uri(&params), text) {
+                    self.documents.insert(uri.to_string(), text.to_string());
+                }
+            }
+            "textDocument/didChange" => {
+                // With full sync, the last change has the whole text
+                let changes = params["contentChanges"].as_array();
+                let text = changes
+                    .and_then(|c| c.last())
+                    .and_then(|c| c["text"].as_str());
+                if let (Some(uri), Some(text)) = (uri(&params), text) {
+                    self.documents.insert(uri.to_string(), text.to_string());
+                }
+            }
+            "textDocument/didClose" => {
+                if let Some(uri) = uri(&params) {
+                    self.documents.remove(uri);
+                }
+            }
+            "workspace/didChangeConfiguration" => {
+                if let Some(options) = params["settings"].get("linkify") {
+                    self.configure(options);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn configure(&mut self, options: &Value) {
+        if options.is_null() {
+            return;
+        }
+        match serde_json::from_value::<LinkFinderOptions>(options.clone()) {
+            Ok(options) => self.finder = LinkFinder::from(options),
+            Err(e) => eprintln!("linkify-lsp: invalid options: {}", e),
+        }
+    }
+}
+
+fn uri(params: &Value) -> Option<&str> {
+    params["textDocument"]["uri"].as_str()
+}
+
+/// The `DocumentLink`s of the text, with ranges in UTF-16 code units as required by LSP.
+fn document_links(finder: &LinkFinder, text: &str) -> Vec<Value> {
+    let index = LineIndex::new(text);
+    finder
+        .links(text)
+        .filter_map(|link| {
+            let target = target(&link)?;
+            let range = index.range_utf16(link.start()..link.end());
+            Some(json!({
+                "range": {"start": position(range.start), "end": position(range.end)},
+                "target": target,
+            }))
+        })
+        .collect()
+}
+
+/// The URL to open for the link. Mentions, hashtags, references without a URL template and
+/// defanged links don't have one.
+fn target(link: &Link<'_>) -> Option<String> {
+    if link.is_defanged() {
+        return None;
+    }
+    match link.kind() {
+        LinkKind::Mention | LinkKind::Hashtag => None,
+        LinkKind::Reference => link.reference_url(),
+        _ => Some(html::href(link, "https")),
+    }
+}
+
+fn position(position: Position) -> Value {
+    json!({"line": position.line(), "character": position.column()})
+}
+
+fn error_response(id: Value, error: Error) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": error.code, "message": error.message},
+    })
+}
+
+/// Read a message with its `Content-Length` header, `None` at the end of the input. Messages that
+/// are too large or not UTF-8 are skipped and result in a parse error.
+fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Result<String, Error>>> {
+    let mut content_length = None;
+    let mut header_too_long = false;
+    loop {
+        let mut line = String::new();
+        if io::Read::take(&mut *input, MAX_HEADER_LEN).read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if !line.ends_with('\n') && line.len() as u64 == MAX_HEADER_LEN {
+            // Skip the rest of the line and the content of the message, if its length is known
+            skip_line(input)?;
+            header_too_long = true;
+            continue;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let mut parts = line.splitn(2, ':');
+        let name = parts.next().unwrap_or("");
+        let value = parts.next().unwrap_or("").trim();
+        if name.eq_ignore_ascii_case("Content-Length") {
+            content_length = value.parse::<u64>().ok();
+        }
+    }
+    if header_too_long {
+        if let Some(length) = content_length {
+            io::copy(&mut io::Read::take(&mut *input, length), &mut io::sink())?;
+        }
+        let message = format!("header is longer than {} bytes", MAX_HEADER_LEN);
+        return Ok(Some(Err(Error::new(PARSE_ERROR, message))));
+    }
+    let length = content_length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    if length > MAX_CONTENT_LENGTH {
+        io::copy(&mut io::Read::take(&mut *input, length), &mut io::sink())?;
+        let message = format!("Content-Length {} is too large", length);
+        return Ok(Some(Err(Error::new(PARSE_ERROR, message))));
+    }
+    let mut content = vec![0; length as usize];
+    input.read_exact(&mut content)?;
+    Ok(Some(
+        String::from_utf8(content).map_err(|e| Error::new(PARSE_ERROR, e.to_string())),
+    ))
+}
+
+/// Skip the input up to and including the next line break, without buffering it.
+fn skip_line<R: BufRead>(input: &mut R) -> io::Result<()> {
+    loop {
+        let buf = input.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(());
+        }
+        match buf.iter().position(|&b| b == b'\n') {
+            Some(i) => {
+                input.consume(i + 1);
+                return Ok(());
+            }
+            None => {
+                let len = buf.len();
+                input.consume(len);
+            }
+        }
+    }
+}
+
+fn send<W: Write>(output: &mut W, message: &Value) {
+    let content = message.to_string();
+    let result = write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )
+    .and_then(|_| output.flush());
+    if let Err(e) = result {
+        eprintln!("linkify-lsp: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,558 @@
+//! The `linkify` command-line tool for finding links in files, see `linkify --help`.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, BufRead, Read, Write};
+use std::process;
+
+use lexopt::prelude::*;
+use linkify::html::{self, HtmlRenderer, LinkAction};
+use linkify::terminal::TerminalRenderer;
+use linkify::{is_known_tld, InputFormat, Link, LinkFinder, LinkFinderOptions, LinkKind};
+use serde::Serialize;
+
+const USAGE: &str = "\
+Usage: linkify [OPTIONS] [FILE]...
+
+Find links in the files (or standard input if there are none or for `-`) and print them.
+
+Output:
+  -o, --output <FORMAT>        Output format [default: plain]:
+                                 plain     one link per line
+                                 grep      file:line:column:link
+                                 json      one JSON object per link
+                                 html      the input as HTML with links (plain input only)
+                                 markdown  the input with Markdown links (plain or Markdown input)
+                                 terminal  the input with terminal hyperlinks (plain or ANSI input)
+  -u, --unique                 Only print the first occurrence of each link
+  -s, --sort                   Sort the links by their text
+
+Finding links:
+  -c, --config <FILE>          Load the options from a TOML file, see `LinkFinderOptions`.
+                               The following options override the file. Rules for specific
+                               schemes (`scheme_policy.rules`) and the `unicode` and
+                               `require_letter` options of mentions and hashtags can only be
+                               set there.
+  -k, --kinds <KINDS>          Kinds of links to find, comma-separated [default: url,email]:
+                               url, email, phone, fediverse_handle, mention, hashtag, reference
+      --input-format <FORMAT>  Format of the input: plain, markdown, html or ansi (text with
+                               escape sequences such as colors) [default: plain]
+      --existing-links         Also print existing links in Markdown or HTML input
+      --url-without-scheme     Also find URLs without a scheme such as `example.org`
+      --no-iri                 Don't find URLs with non-ASCII characters
+      --email-without-dot      Also find emails without a dot in the domain such as `root@localhost`
+      --known-tlds             Only find URLs without a scheme and emails with a known TLD
+      --allow-schemes <LIST>   Only find URLs with one of these schemes, comma-separated
+      --deny-schemes <LIST>    Don't find URLs with any of these schemes, comma-separated
+      --opaque-schemes         Also find URIs like `mailto:`, `tel:` and `urn:` without `//`
+      --defanged               Find defanged links like `hxxps://example[.]com` and print
+                               them refanged (plain and grep output)
+
+Mentions and hashtags:
+      --mention-chars <CHARS>      Characters allowed in mentions besides letters and digits
+      --mention-max-length <N>     Maximum length of mentions
+      --hashtag-chars <CHARS>      Characters allowed in hashtags besides letters and digits
+      --hashtag-max-length <N>     Maximum length of hashtags
+
+References:
+      --repository <OWNER/REPO>    Repository of references like `#123`
+      --projects <LIST>            Project keys of tickets like `PROJ-123`, comma-separated
+      --issue-url <TEMPLATE>       URL template for issues, e.g.
+                                   `https://github.com/{owner}/{repo}/issues/{n}`
+      --ticket-url <TEMPLATE>      URL template for tickets, e.g.
+                                   `https://example.atlassian.net/browse/{project}-{n}`
+      --commit-url <TEMPLATE>      URL template for commits, e.g.
+                                   `https://github.com/{owner}/{repo}/commit/{sha}`
+      --no-issues                  Don't find issues
+      --no-commits                 Don't find commits
+
+  -h, --help                   Print this help
+  -V, --version                Print the version
+";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Output {
+    Plain,
+    Grep,
+    Json,
+    Html,
+    Markdown,
+    Terminal,
+}
+
+/// The parsed command-line arguments.
+#[derive(Default)]
+struct Args {
+    output: Option<Output>,
+    unique: bool,
+    sort: bool,
+    config: Option<String>,
+    kinds: Option<Vec<LinkKind>>,
+    input_format: Option<InputFormat>,
+    existing_links: bool,
+    url_without_scheme: bool,
+    no_iri: bool,
+    email_without_dot: bool,
+    known_tlds: bool,
+    allow_schemes: Vec<String>,
+    deny_schemes: Vec<String>,
+    opaque_schemes: bool,
+    defanged: bool,
+    mention_chars: Option<String>,
+    mention_max_length: Option<usize>,
+    hashtag_chars: Option<String>,
+    hashtag_max_length: Option<usize>,
+    repository: Option<(String, String)>,
+    projects: Vec<String>,
+    issue_url: Option<String>,
+    ticket_url: Option<String>,
+    commit_url: Option<String>,
+    no_issues: bool,
+    no_commits: bool,
+    files: Vec<String>,
+}
+
+/// A link with its position in the input, for printing.
+#[derive(Serialize)]
+struct Match<'a> {
+    file: &'a str,
+    line: usize,
+    column: usize,
+    #[serde(flatten)]
+    link: Link<'a>,
+}
+
+fn main() {
+    let result = parse_args().map_err(|e| e.to_string()).and_then(run);
+    if let Err(message) = result {
+        eprintln!("linkify: {}", message);
+        process::exit(2);
+    }
+}
+
+fn parse_args() -> Result<Args, lexopt::Error> {
+    let mut args = Args::default();
+    let mut parser = lexopt::Parser::from_env();
+    while let Some(arg) = parser.next()? {
+        match arg {
+            Short('o') | Long("output") => {
+                args.output = Some(match parser.value()?.string()?.as_str() {
+                    "plain" => Output::Plain,
+                    "grep" => Output::Grep,
+                    "json" => Output::Json,
+                    "html" => Output::Html,
+                    "markdown" => Output::Markdown,
+                    "terminal" => Output::Terminal,
+                    other => return Err(format!("unknown output format '{}'", other).into()),
+                })
+            }
+            Short('u') | Long("unique") => args.unique = true,
+            Short('s') | Long("sort") => args.sort = true,
+            Short('c') | Long("config") => args.config = Some(parser.value()?.string()?),
+            Short('k') | Long("kinds") => {
+                let kinds = list(&parser.value()?.string()?)
+                    .iter()
+                    .map(|kind| parse_kind(kind))
+                    .collect::<Result<_, _>>()?;
+                args.kinds = Some(kinds);
+            }
+            Long("input-format") => {
+                args.input_format = Some(match parser.value()?.string()?.as_str() {
+                    "plain" => InputFormat::Plain,
+                    "markdown" => InputFormat::Markdown,
+                    "html" => InputFormat::Html,
+                    "ansi" => InputFormat::Ansi,
+                    other => return Err(format!("unknown input format '{}'", other).into()),
+                })
+            }
+            Long("existing-links") => args.existing_links = true,
+            Long("url-without-scheme") => args.url_without_scheme = true,
+            Long("no-iri") => args.no_iri = true,
+            Long("email-without-dot") => args.email_without_dot = true,
+            Long("known-tlds") => args.known_tlds = true,
+            Long("allow-schemes") => args.allow_schemes = list(&parser.value()?.string()?),
+            Long("deny-schemes") => args.deny_schemes = list(&parser.value()?.string()?),
+            Long("opaque-schemes") => args.opaque_schemes = true,
+            Long("defanged") => args.defanged = true,
+            Long("mention-chars") => args.mention_chars = Some(parser.value()?.string()?),
+            Long("mention-max-length") => args.mention_max_length = Some(parser.value()?.parse()?),
+            Long("hashtag-chars") => args.hashtag_chars = Some(parser.value()?.string()?),
+            Long("hashtag-max-length") => args.hashtag_max_length = Some(parser.value()?.parse()?),
+            Long("repository") => {
+                let value = parser.value()?.string()?;
+                match value.find('/') {
+                    Some(slash) => {
+                        args.repository =
+                            Some((value[..slash].to_string(), value[slash + 1..].to_string()))
+                    }
+                    None => return Err(format!("repository '{}' is not OWNER/REPO", value).into()),
+                }
+            }
+            Long("projects") => args.projects = list(&parser.value()?.string()?),
+            Long("issue-url") => args.issue_url = Some(parser.value()?.string()?),
+            Long("ticket-url") => args.ticket_url = Some(parser.value()?.string()?),
+            Long("commit-url") => args.commit_url = Some(parser.value()?.string()?),
+            Long("no-issues") => args.no_issues = true,
+            Long("no-commits") => args.no_commits = true,
+            Short('h') | Long("help") => {
+                print!("{}", USAGE);
+                process::exit(0);
+            }
+            Short('V') | Long("version") => {
+                println!("linkify {}", env!("CARGO_PKG_VERSION"));
+                process::exit(0);
+            }
+            Value(file) => args.files.push(file.string()?),
+            _ => return Err(arg.unexpected()),
+        }
+    }
+    Ok(args)
+}
+
+fn list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_kind(kind: &str) -> Result<LinkKind, String> {
+    match kind {
+        "url" => Ok(LinkKind::Url),
+        "email" => Ok(LinkKind::Email),
+        "phone" => Ok(LinkKind::Phone),
+        "fediverse_handle" => Ok(LinkKind::FediverseHandle),
+        "mention" => Ok(LinkKind::Mention),
+        "hashtag" => Ok(LinkKind::Hashtag),
+        "reference" => Ok(LinkKind::Reference),
+        _ => Err(format!("unknown kind of link '{}'", kind)),
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let finder = finder(&args)?;
+    let output = args.output.unwrap_or(Output::Plain);
+    let options = finder.options();
+    let (input_format, refang) = (options.input_format, options.defanged);
+    match output {
+        Output::Html | Output::Markdown | Output::Terminal if args.unique || args.sort => {
+            return Err(
+                "--unique and --sort can't be used with html, markdown or terminal output".into(),
+            )
+        }
+        Output::Html if input_format != InputFormat::Plain => {
+            return Err("html output needs plain input".into())
+        }
+        Output::Markdown if !matches!(input_format, InputFormat::Plain | InputFormat::Markdown) => {
+            return Err("markdown output needs plain or markdown input".into())
+        }
+        Output::Terminal if !matches!(input_format, InputFormat::Plain | InputFormat::Ansi) => {
+            return Err("terminal output needs plain or ansi input".into())
+        }
+        _ => {}
+    }
+
+    let mut files = args.files.clone();
+    if files.is_empty() {
+        files.push("-".to_string());
+    }
+    if output == Output::Plain && input_format == InputFormat::Plain && !args.sort {
+        return stream(&finder, &files, args.unique, refang);
+    }
+
+    let inputs = files
+        .iter()
+        .map(|file| read(file))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut out = String::new();
+    match output {
+        Output::Html => {
+            let mut renderer = HtmlRenderer::new();
+            renderer.filter(|link| {
+                if link.is_defanged() {
+                    LinkAction::Text
+                } else {
+                    LinkAction::Link
+                }
+            });
+            for (_, bytes) in &inputs {
+                renderer
+                    .render_to(finder.spans(&String::from_utf8_lossy(bytes)), &mut out)
+                    .expect("writing to a String doesn't fail");
+            }
+        }
+        Output::Markdown => {
+            for (_, bytes) in &inputs {
+                write_markdown(&finder, &String::from_utf8_lossy(bytes), &mut out);
+            }
+        }
+        Output::Terminal => {
+            let renderer = TerminalRenderer::new();
+            for (_, bytes) in &inputs {
+                renderer
+                    .render_to(finder.spans(&String::from_utf8_lossy(bytes)), &mut out)
+                    .expect("writing to a String doesn't fail");
+            }
+        }
+        Output::Plain | Output::Grep | Output::Json => {
+            let mut matches = Vec::new();
+            for (name, bytes) in &inputs {
+                find_matches(&finder, name, bytes, &mut matches);
+            }
+            if args.unique {
+                let mut seen = HashSet::new();
+                matches.retain(|m| seen.insert(m.link.as_str()));
+            }
+            if args.sort {
+                matches.sort_by(|a, b| a.link.as_str().cmp(b.link.as_str()));
+            }
+            for m in &matches {
+                write_match(m, output, refang, &mut out)?;
+            }
+        }
+    }
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    match stdout.write_all(out.as_bytes()) {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
+
+fn finder(args: &Args) -> Result<LinkFinder, String> {
+    let mut options = match &args.config {
+        Some(path) => {
+            let config = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+            toml::from_str(&config).map_err(|e| format!("{}: {}", path, e))?
+        }
+        None => LinkFinderOptions::default(),
+    };
+    if let Some(kinds) = &args.kinds {
+        options.kinds = kinds.clone();
+    }
+    if let Some(input_format) = args.input_format {
+        options.input_format = input_format;
+    }
+    if args.existing_links {
+        options.existing_links = true;
+    }
+    if args.url_without_scheme {
+        options.url_must_have_scheme = false;
+    }
+    if args.no_iri {
+        options.url_can_be_iri = false;
+    }
+    if args.email_without_dot {
+        options.email_domain_must_have_dot = false;
+    }
+    if args.defanged {
+        options.defanged = true;
+    }
+
+    let mut mention_rules = options.mention_rules.clone();
+    if let Some(chars) = &args.mention_chars {
+        mention_rules = mention_rules.chars(chars);
+    }
+    if let Some(max_length) = args.mention_max_length {
+        mention_rules = mention_rules.max_length(max_length);
+    }
+    options.mention_rules = mention_rules;
+
+    let mut hashtag_rules = options.hashtag_rules.clone();
+    if let Some(chars) = &args.hashtag_chars {
+        hashtag_rules = hashtag_rules.chars(chars);
+    }
+    if let Some(max_length) = args.hashtag_max_length {
+        hashtag_rules = hashtag_rules.max_length(max_length);
+    }
+    options.hashtag_rules = hashtag_rules;
+
+    let mut reference_rules = options.reference_rules.clone();
+    if let Some((owner, repo)) = &args.repository {
+        reference_rules = reference_rules.repository(owner, repo);
+    }
+    if !args.projects.is_empty() {
+        let projects: Vec<&str> = args.projects.iter().map(String::as_str).collect();
+        reference_rules = reference_rules.projects(&projects);
+    }
+    if let Some(template) = &args.issue_url {
+        reference_rules = reference_rules.issue_url(template);
+    }
+    if let Some(template) = &args.ticket_url {
+        reference_rules = reference_rules.ticket_url(template);
+    }
+    if let Some(template) = &args.commit_url {
+        reference_rules = reference_rules.commit_url(template);
+    }
+    if args.no_issues {
+        reference_rules = reference_rules.issues(false);
+    }
+    if args.no_commits {
+        reference_rules = reference_rules.commits(false);
+    }
+    options.reference_rules = reference_rules;
+
+    if !args.allow_schemes.is_empty() {
+        let schemes: Vec<&str> = args.allow_schemes.iter().map(String::as_str).collect();
+        options.scheme_policy.allow(&schemes);
+    }
+    let schemes: Vec<&str> = args.deny_schemes.iter().map(String::as_str).collect();
+    options.scheme_policy.deny(&schemes);
+    if args.opaque_schemes {
+        options.scheme_policy.common_opaque_schemes();
+    }
+
+    let mut finder = LinkFinder::from(options);
+    if args.known_tlds {
+        finder.tld_validator(is_known_tld);
+    }
+    Ok(finder)
+}
+
+/// Print the links in the files one per line while reading them, for plain input and output.
+fn stream(finder: &LinkFinder, files: &[String], unique: bool, refang: bool) -> Result<(), String> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    let mut seen = HashSet::new();
+    for file in files {
+        let (name, reader): (&str, Box<dyn BufRead>) = if file == "-" {
+            ("<stdin>", Box::new(stdin.lock()))
+        } else {
+            let f = fs::File::open(file).map_err(|e| format!("{}: {}", file, e))?;
+            (file, Box::new(io::BufReader::new(f)))
+        };
+        for link in finder.links_reader(reader) {
+            let link = link.map_err(|e| format!("{}: {}", name, e))?;
+            if unique && !seen.insert(link.as_str().to_string()) {
+                continue;
+            }
+            let text = if refang {
+                link.refanged()
+            } else {
+                link.as_str().to_string()
+            };
+            match writeln!(stdout, "{}", text) {
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+                Err(e) => return Err(e.to_string()),
+                Ok(()) => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read a file (or standard input for `-`) as bytes, which can contain invalid UTF-8.
+fn read(file: &str) -> Result<(String, Vec<u8>), String> {
+    let mut bytes = Vec::new();
+    if file == "-" {
+        io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("<stdin>: {}", e))?;
+        Ok(("<stdin>".to_string(), bytes))
+    } else {
+        bytes = fs::read(file).map_err(|e| format!("{}: {}", file, e))?;
+        Ok((file.to_string(), bytes))
+    }
+}
+
+fn find_matches<'a>(
+    finder: &LinkFinder,
+    file: &'a str,
+    bytes: &'a [u8],
+    matches: &mut Vec<Match<'a>>,
+) {
+    // Lines and columns start at 1, columns are in bytes like in grep. `LineIndex` needs valid
+    // UTF-8, so the lines are found in the bytes here.
+    let mut line_starts = vec![0];
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'\n' => line_starts.push(i + 1),
+            b'\r' if bytes.get(i + 1) != Some(&b'\n') => line_starts.push(i + 1),
+            _ => {}
+        }
+    }
+    for link in finder.links_bytes(bytes) {
+        let line = match line_starts.binary_search(&link.start()) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+        matches.push(Match {
+            file,
+            line: line + 1,
+            column: link.start() - line_starts[line] + 1,
+            link,
+        });
+    }
+}
+
+fn write_match(
+    m: &Match<'_>,
+    output: Output,
+    refang: bool,
+    out: &mut String,
+) -> Result<(), String> {
+    let text = if refang {
+        m.link.refanged()
+    } else {
+        m.link.as_str().to_string()
+    };
+    match output {
+        Output::Plain => writeln!(out, "{}", text).expect("writing to a String doesn't fail"),
+        Output::Grep => writeln!(out, "{}:{}:{}:{}", m.file, m.line, m.column, text)
+            .expect("writing to a String doesn't fail"),
+        _ => {
+            let json = serde_json::to_string(m).map_err(|e| e.to_string())?;
+            out.push_str(&json);
+            out.push('\n');
+        }
+    }
+    Ok(())
+}
+
+/// Write the text with links as Markdown links, existing links in the input are kept.
+fn write_markdown(finder: &LinkFinder, text: &str, out: &mut String) {
+    finder
+        .replace_all_to(text, out, markdown_link)
+        .expect("writing to a String doesn't fail");
+}
+
+fn markdown_link<'l>(link: &'l Link<'_>) -> Cow<'l, str> {
+    let href = match link.kind() {
+        // Defanged links are not made clickable again
+        _ if link.is_existing() || link.is_defanged() => None,
+        LinkKind::Mention | LinkKind::Hashtag => None,
+        LinkKind::Reference => link.reference_url(),
+        _ => Some(html::href(link, "https")),
+    };
+    match href {
+        // Autolinks like `<https://example.org>` and `<foo@example.org>`
+        Some(href) if href == link.as_str() || *link.kind() == LinkKind::Email => {
+            Cow::Owned(format!("<{}>", link.as_str()))
+        }
+        Some(href) => {
+            let mut markdown = String::from("[");
+            escape_markdown(link.as_str(), "\\`*_[]<>", &mut markdown);
+            markdown.push_str("](");
+            escape_markdown(&href, "\\()<>", &mut markdown);
+            markdown.push(')');
+            Cow::Owned(markdown)
+        }
+        None => Cow::Borrowed(link.as_str()),
+    }
+}
+
+fn escape_markdown(text: &str, special: &str, out: &mut String) {
+    for c in text.chars() {
+        if special.contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
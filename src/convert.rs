@@ -0,0 +1,47 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::finder::{Link, LinkKind};
+use crate::html;
+
+/// The reason why a link can't be converted to a [`url::Url`], see [`Link::to_url`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ToUrlError {
+    /// The kind of link doesn't have a URL, e.g. mentions, or references without a URL template.
+    NoUrl(LinkKind),
+    /// The link is not a valid URL according to the WHATWG URL Standard, e.g. because the host
+    /// contains characters that are not allowed.
+    Invalid(::url::ParseError),
+}
+
+impl fmt::Display for ToUrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToUrlError::NoUrl(kind) => write!(f, "link of kind {:?} doesn't have a URL", kind),
+            ToUrlError::Invalid(e) => write!(f, "link is not a valid URL: {}", e),
+        }
+    }
+}
+
+impl Error for ToUrlError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ToUrlError::NoUrl(_) => None,
+            ToUrlError::Invalid(e) => Some(e),
+        }
+    }
+}
+
+pub(crate) fn to_url(link: &Link<'_>) -> Result<::url::Url, ToUrlError> {
+    let url = match link.kind() {
+        LinkKind::Url | LinkKind::Email | LinkKind::Phone | LinkKind::FediverseHandle => {
+            html::href(link, "https")
+        }
+        LinkKind::Reference => link
+            .reference_url()
+            .ok_or(ToUrlError::NoUrl(LinkKind::Reference))?,
+        kind => return Err(ToUrlError::NoUrl(*kind)),
+    };
+    ::url::Url::parse(&url).map_err(ToUrlError::Invalid)
+}
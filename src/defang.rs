@@ -0,0 +1,201 @@
+use crate::components::Components;
+use crate::finder::{Link, LinkKind, Segment};
+
+/// Replace the defang conventions in a segment so that links can be found in it.
+pub(crate) fn refang_segment(text: &str, segment: Segment) -> Segment {
+    match segment {
+        Segment::Text(range) => match refang(&text[range.clone()], range.start) {
+            Some((text, offsets)) => Segment::Mapped { text, offsets },
+            None => Segment::Text(range),
+        },
+        Segment::Mapped { text, offsets } => match refang(&text, 0) {
+            Some((text, mapped)) => Segment::Mapped {
+                text,
+                offsets: mapped.into_iter().map(|i| offsets[i]).collect(),
+            },
+            None => Segment::Mapped { text, offsets },
+        },
+        segment => segment,
+    }
+}
+
+/// Parse the components of a defanged link with the conventions replaced, and map the ranges
+/// back to the input.
+pub(crate) fn components(
+    text: &str,
+    start: usize,
+    kind: &LinkKind,
+    has_scheme: bool,
+) -> Components {
+    match refang_link(text, start, kind, has_scheme) {
+        Some((refanged, offsets)) => {
+            Components::parse(&refanged, 0, kind, has_scheme).map(|i| offsets[i])
+        }
+        None => Components::parse(text, start, kind, has_scheme),
+    }
+}
+
+/// Whether a link that was found in refanged text uses defang conventions, see `refang_link`.
+pub(crate) fn is_defanged(text: &str, kind: &LinkKind, has_scheme: bool) -> bool {
+    refang_link(text, 0, kind, has_scheme).is_some()
+}
+
+/// Replace the defang conventions in the scheme and host of a link and the separators up to the
+/// host, e.g. `hxxps://example[.]com/a[.]b` becomes `https://example.com/a[.]b` and
+/// `user[at]example[.]com` becomes `user@example.com`. Conventions in other components like the
+/// path or the userinfo are kept, they are often not meant as defanging.
+///
+/// Returns the refanged text and the offsets like `refang`, or `None` if there was nothing to
+/// replace.
+pub(crate) fn refang_link(
+    text: &str,
+    start: usize,
+    kind: &LinkKind,
+    has_scheme: bool,
+) -> Option<(String, Vec<usize>)> {
+    // Find the parts to replace in the fully refanged link, then map them back to the text
+    let (refanged, offsets) = refang(text, 0)?;
+    let components = Components::parse(&refanged, 0, kind, has_scheme);
+    let end = match (components.host(), components.scheme()) {
+        (Some(host), _) => host.end,
+        // The `:` after the scheme too, e.g. in `mailto[:]`
+        (None, Some(scheme)) => scheme.end + 1,
+        (None, None) => return None,
+    };
+    let end = offsets[end];
+    let kept = components
+        .userinfo()
+        .or_else(|| components.local_part())
+        .map(|range| offsets[range.start]..offsets[range.end]);
+    refang_where(text, start, |i| {
+        i < end && !matches!(&kept, Some(kept) if kept.contains(&i))
+    })
+}
+
+/// Replace the defang conventions, returning the refanged text and the offsets that map each byte
+/// index of it (and its length) to the index in the input, starting at `start`. Returns `None` if
+/// there was nothing to replace.
+pub(crate) fn refang(text: &str, start: usize) -> Option<(String, Vec<usize>)> {
+    refang_where(text, start, |_| true)
+}
+
+/// Like `refang`, but only replace the conventions at the indexes that `replace` accepts.
+fn refang_where<F: Fn(usize) -> bool>(
+    text: &str,
+    start: usize,
+    replace: F,
+) -> Option<(String, Vec<usize>)> {
+    let mut refanged = String::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+    let mut replaced = false;
+    let mut i = 0;
+    while i < text.len() {
+        if let Some((replacement, len)) = defanged_at(text, i).filter(|_| replace(i)) {
+            refanged.push_str(replacement);
+            for _ in 0..replacement.len() {
+                offsets.push(start + i);
+            }
+            replaced = true;
+            i += len;
+        } else {
+            let c = text[i..].chars().next().unwrap();
+            refanged.push(c);
+            for j in 0..c.len_utf8() {
+                offsets.push(start + i + j);
+            }
+            i += c.len_utf8();
+        }
+    }
+    offsets.push(start + text.len());
+    if replaced {
+        Some((refanged, offsets))
+    } else {
+        None
+    }
+}
+
+/// Check for a defanged part at the index, returning the replacement and the length of the part.
+fn defanged_at(text: &str, i: usize) -> Option<(&'static str, usize)> {
+    let s = &text.as_bytes()[i..];
+    let close = match s[0] {
+        b'[' => b']',
+        b'(' => b')',
+        b'{' => b'}',
+        b'h' | b'H' | b'f' | b'F' => return defanged_scheme(text, i),
+        _ => return None,
+    };
+    // `[.]`, `[dot]`, `(at)`, `{://}` and so on
+    let close_index = s.iter().take(6).position(|&b| b == close)?;
+    let content = &text[i + 1..i + close_index];
+    let replacement = if content == "." || content.eq_ignore_ascii_case("dot") {
+        "."
+    } else if content == "@" || content.eq_ignore_ascii_case("at") {
+        "@"
+    } else if content == ":" {
+        ":"
+    } else if content == "://" {
+        "://"
+    } else {
+        return None;
+    };
+    Some((replacement, close_index + 1))
+}
+
+/// Check for a defanged scheme like `hxxps` that is followed by `:` or a defanged `[:`.
+fn defanged_scheme(text: &str, i: usize) -> Option<(&'static str, usize)> {
+    if matches!(text[..i].chars().next_back(), Some(c) if c.is_alphanumeric()) {
+        return None;
+    }
+    let s = &text.as_bytes()[i..];
+    let (replacement, len) = if starts_with_ignore_case(s, b"hxxps") {
+        ("https", 5)
+    } else if starts_with_ignore_case(s, b"hxxp") {
+        ("http", 4)
+    } else if starts_with_ignore_case(s, b"fxp") {
+        ("ftp", 3)
+    } else {
+        return None;
+    };
+    match s.get(len) {
+        Some(b':') | Some(b'[') | Some(b'(') | Some(b'{') => Some((replacement, len)),
+        _ => None,
+    }
+}
+
+fn starts_with_ignore_case(s: &[u8], prefix: &[u8]) -> bool {
+    s.len() >= prefix.len() && s[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+/// Defang a URL or email, e.g. `https://example.com` becomes `hxxps://example[.]com`.
+pub(crate) fn defang_link(link: &Link<'_>) -> String {
+    let text = link.refanged();
+    let components = Components::parse(&text, 0, link.kind(), link.has_scheme());
+    let mut defanged = String::with_capacity(text.len() + 8);
+    let mut last = 0;
+    if let Some(scheme) = components.scheme() {
+        let scheme_text = &text[scheme.clone()];
+        let replacement = if scheme_text.eq_ignore_ascii_case("https") {
+            "hxxps"
+        } else if scheme_text.eq_ignore_ascii_case("http") {
+            "hxxp"
+        } else if scheme_text.eq_ignore_ascii_case("ftp") {
+            "fxp"
+        } else {
+            scheme_text
+        };
+        defanged.push_str(replacement);
+        last = scheme.end;
+    }
+    if let Some(host) = components.host() {
+        defanged.push_str(&text[last..host.start]);
+        if *link.kind() == LinkKind::Email {
+            // Replace the `@` in front of the domain
+            defanged.pop();
+            defanged.push_str("[at]");
+        }
+        defanged.push_str(&text[host.clone()].replace('.', "[.]"));
+        last = host.end;
+    }
+    defanged.push_str(&text[last..]);
+    defanged
+}
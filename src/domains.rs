@@ -11,7 +11,13 @@
 //!
 //! host        = IP-literal / IPv4address / reg-name
 //!
-//! IP-literal = "[" ( IPv6address / IPvFuture  ) "]"
+//! IP-literal = "[" ( IPv6address / IPv6addrz / IPvFuture  ) "]"
+//!
+//! IPv6addrz   = IPv6address "%25" ZoneID
+//!
+//! ZoneID      = 1*( unreserved / pct-encoded )
+//!
+//! IPvFuture   = "v" 1*HEXDIG "." 1*( unreserved / sub-delims / ":" )
 //!
 //! IPv4address = dec-octet "." dec-octet "." dec-octet "." dec-octet
 //!
@@ -26,6 +32,86 @@
 //! ```
 
 use std::char;
+use std::fmt;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::Arc;
+
+/// The kind of host of a link, e.g. a domain name or an IP address.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum HostKind {
+    /// A domain name like `example.org` or `localhost`.
+    Domain,
+    /// An IPv4 address like `127.0.0.1`.
+    Ipv4,
+    /// An IPv6 address in brackets like `[::1]`, optionally with a zone ID like `[fe80::1%25eth0]`.
+    Ipv6,
+    /// A future IP address format in brackets like `[v1.fe80::a+en1]`.
+    IpvFuture,
+    /// Something else that is allowed for schemes that don't require a host, e.g. `+19995551234`.
+    Other,
+}
+
+/// User-supplied check for top-level domains, see `LinkFinder::tld_validator`.
+#[derive(Clone)]
+pub struct TldValidator(pub Arc<dyn Fn(&str) -> bool + Send + Sync + RefUnwindSafe + UnwindSafe>);
+
+impl TldValidator {
+    /// Check the TLD of a host that was found by `find_authority_end` (with `last_dot` as returned
+    /// by it). Hosts without a dot and IPv4 addresses are always accepted.
+    pub fn accepts(&self, host: &str, last_dot: Option<usize>) -> bool {
+        let last_dot = match last_dot {
+            Some(last_dot) => last_dot,
+            None => return true,
+        };
+        let tld = &host[last_dot + 1..];
+        let tld = &tld[..tld.find(':').unwrap_or(tld.len())];
+        if tld.bytes().all(|b| b.is_ascii_digit()) {
+            return true;
+        }
+        (self.0)(tld)
+    }
+}
+
+impl fmt::Debug for TldValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TldValidator")
+    }
+}
+
+/// Check whether the specified top-level domain (e.g. `com`) is in the list of known TLDs. The
+/// list is from the ICANN section of the [Public Suffix List](https://publicsuffix.org/).
+///
+/// The check is case-insensitive. Internationalized TLDs can be in Unicode (e.g. `рф`) or Punycode
+/// (e.g. `xn--p1ai`) form.
+///
+/// Note that many file extensions are real TLDs too, e.g. `md`, `rs`, `py` or `zip`, so file names
+/// such as `README.md` are still found as links with this check.
+///
+/// This can be used with [`LinkFinder::tld_validator`](crate::LinkFinder::tld_validator):
+///
+/// ```
+/// use linkify::LinkFinder;
+///
+/// let mut finder = LinkFinder::new();
+/// finder.url_must_have_scheme(false);
+/// finder.tld_validator(linkify::is_known_tld);
+///
+/// let links: Vec<_> = finder.links("See config.yaml and example.com").collect();
+/// assert_eq!(1, links.len());
+/// assert_eq!("example.com", links[0].as_str());
+/// ```
+#[cfg(feature = "tlds")]
+pub fn is_known_tld(tld: &str) -> bool {
+    use crate::tlds::TLDS;
+
+    if tld.chars().any(char::is_uppercase) {
+        let lowercase = tld.to_lowercase();
+        TLDS.binary_search(&lowercase.as_str()).is_ok()
+    } else {
+        TLDS.binary_search(&tld).is_ok()
+    }
+}
 
 pub(crate) fn find_authority_end(
     s: &str,
@@ -44,6 +130,8 @@ pub(crate) fn find_authority_end(
     let mut all_numeric = true;
     let mut maybe_host = true;
     let mut host_ended = false;
+    let mut port_colon = None;
+    let mut host_start = 0;
 
     for (i, c) in s.char_indices() {
         let can_be_last = match c {
@@ -131,6 +219,10 @@ pub(crate) fn find_authority_end(
                 // Don't advance the last dot when we get to port numbers
                 maybe_last_dot = last_dot;
 
+                if port_colon.is_none() {
+                    port_colon = Some(i);
+                }
+
                 false
             }
             '@' => {
@@ -150,9 +242,36 @@ pub(crate) fn find_authority_end(
                 all_numeric = true;
                 maybe_host = true;
                 host_ended = false;
+                port_colon = None;
+                host_start = i + 1;
 
                 false
             }
+            '[' => {
+                if i != host_start {
+                    // IP literals need to be the whole host
+                    break;
+                }
+
+                return match find_ip_literal_end(&s[i..]) {
+                    Some(literal_end) => {
+                        let literal_end = i + literal_end;
+                        let port = if port_allowed && s[literal_end..].starts_with(':') {
+                            s[literal_end + 1..]
+                                .find(|c: char| !c.is_ascii_digit())
+                                .unwrap_or(s.len() - literal_end - 1)
+                        } else {
+                            0
+                        };
+                        if port > 0 {
+                            (Some(literal_end + 1 + port), None)
+                        } else {
+                            (Some(literal_end), None)
+                        }
+                    }
+                    None => (None, None),
+                };
+            }
             '/' => {
                 if !require_host {
                     // For schemes where we allow anything, we want to stop at delimiter characters
@@ -174,6 +293,20 @@ pub(crate) fn find_authority_end(
         }
     }
 
+    if !port_allowed {
+        if let Some(port_colon) = port_colon {
+            // The colon wasn't followed by userinfo, so it would start a port. Ports are not
+            // allowed, so the authority ends before it.
+            return find_authority_end(
+                &s[..port_colon],
+                false,
+                require_host,
+                false,
+                iri_parsing_enabled,
+            );
+        }
+    }
+
     if require_host {
         if maybe_host {
             if all_numeric {
@@ -208,3 +341,155 @@ fn valid_tld(tld: &str) -> bool {
         .count()
         >= 2
 }
+
+/// Find the end of an IP literal such as `[::1]`, starting at the `[`. Returns the index after the
+/// `]` if it's a valid literal.
+fn find_ip_literal_end(s: &str) -> Option<usize> {
+    let close = s.find(']')?;
+    if is_ip_literal(&s[1..close]) {
+        Some(close + 1)
+    } else {
+        None
+    }
+}
+
+/// Check whether the part between `[` and `]` is a valid IPv6 address (with optional zone ID) or
+/// IPvFuture.
+fn is_ip_literal(s: &str) -> bool {
+    if s.starts_with('v') || s.starts_with('V') {
+        return is_ipv_future(&s[1..]);
+    }
+
+    match s.find('%') {
+        Some(percent) => is_ipv6(&s[..percent]) && is_zone_id(&s[percent + 1..]),
+        None => is_ipv6(s),
+    }
+}
+
+fn is_ipv_future(s: &str) -> bool {
+    let dot = match s.find('.') {
+        Some(dot) => dot,
+        None => return false,
+    };
+    let version = &s[..dot];
+    let rest = &s[dot + 1..];
+    !version.is_empty()
+        && version.bytes().all(|b| b.is_ascii_hexdigit())
+        && !rest.is_empty()
+        && rest.chars().all(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' => true,
+            // unreserved
+            '-' | '.' | '_' | '~' => true,
+            // sub-delims
+            '!' | '$' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | ';' | '=' => true,
+            ':' => true,
+            _ => false,
+        })
+}
+
+/// Zone IDs should have the `%` percent-encoded as `%25` (RFC 6874), but a plain `%` as in
+/// `fe80::1%eth0` is common too.
+fn is_zone_id(s: &str) -> bool {
+    let zone = if s.len() > 2 && s.starts_with("25") {
+        &s[2..]
+    } else {
+        s
+    };
+    !zone.is_empty()
+        && zone
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~' | '%'))
+}
+
+/// Check for an IPv6 address according to RFC 4291, e.g. `2001:db8::1` or `::ffff:192.0.2.1`.
+pub(crate) fn is_ipv6(s: &str) -> bool {
+    match s.find("::") {
+        Some(double_colon) => {
+            let head = &s[..double_colon];
+            let tail = &s[double_colon + 2..];
+            if tail.contains("::") {
+                return false;
+            }
+            match (
+                count_ipv6_pieces(head, false),
+                count_ipv6_pieces(tail, true),
+            ) {
+                // The `::` stands for at least one piece of zeros
+                (Some(head), Some(tail)) => head + tail <= 7,
+                _ => false,
+            }
+        }
+        None => count_ipv6_pieces(s, true) == Some(8),
+    }
+}
+
+/// Count the 16-bit pieces of a (part of an) IPv6 address, where an IPv4 address at the end counts
+/// as two pieces.
+fn count_ipv6_pieces(s: &str, ipv4_allowed: bool) -> Option<usize> {
+    if s.is_empty() {
+        return Some(0);
+    }
+
+    let mut count = 0;
+    let mut pieces = s.split(':').peekable();
+    while let Some(piece) = pieces.next() {
+        let last = pieces.peek().is_none();
+        if last && ipv4_allowed && piece.contains('.') {
+            if !is_ipv4(piece) {
+                return None;
+            }
+            count += 2;
+        } else if !piece.is_empty()
+            && piece.len() <= 4
+            && piece.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            count += 1;
+        } else {
+            return None;
+        }
+    }
+    Some(count)
+}
+
+/// Check for an IPv4 address with 4 decimal octets without leading zeros, e.g. `192.0.2.1`.
+pub(crate) fn is_ipv4(s: &str) -> bool {
+    let mut count = 0;
+    for octet in s.split('.') {
+        count += 1;
+        let valid = !octet.is_empty()
+            && octet.len() <= 3
+            && octet.bytes().all(|b| b.is_ascii_digit())
+            && !(octet.len() > 1 && octet.starts_with('0'))
+            && octet.parse::<u8>().is_ok();
+        if !valid {
+            return false;
+        }
+    }
+    count == 4
+}
+
+/// Classify a host that was already found by `find_authority_end`.
+pub(crate) fn host_kind(host: &str) -> HostKind {
+    if host.starts_with("[v") || host.starts_with("[V") {
+        HostKind::IpvFuture
+    } else if host.starts_with('[') {
+        HostKind::Ipv6
+    } else if host.bytes().all(|b| b.is_ascii_digit() || b == b'.') && host.split('.').count() == 4
+    {
+        // Same as in `find_authority_end`, this doesn't check the range of the numbers
+        HostKind::Ipv4
+    } else if !host.is_empty()
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || !c.is_ascii())
+        })
+    {
+        HostKind::Domain
+    } else {
+        HostKind::Other
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::Range;
 
-use crate::domains::find_authority_end;
+use crate::domains::{find_authority_end, TldValidator};
 use crate::scanner::Scanner;
 
 /// Scan for email address starting from the trigger character "@".
@@ -9,6 +9,7 @@ use crate::scanner::Scanner;
 /// quoting in local part.
 pub struct EmailScanner {
     pub domain_must_have_dot: bool,
+    pub tld_validator: Option<TldValidator>,
 }
 
 impl Scanner for EmailScanner {
@@ -53,19 +54,27 @@ impl EmailScanner {
 
     // See "Domain" in RFC 5321, plus extension of "sub-domain" in RFC 6531
     fn find_end(&self, s: &str) -> Option<usize> {
+        if s.starts_with('[') {
+            // Address literals like `[IPv6:::1]` are not supported
+            return None;
+        }
         if let (Some(end), last_dot) = find_authority_end(s, false, true, false, true) {
-            if !self.domain_must_have_dot || last_dot.is_some() {
-                Some(end)
-            } else {
-                None
+            if self.domain_must_have_dot && last_dot.is_none() {
+                return None;
+            }
+            if let Some(validator) = &self.tld_validator {
+                if !validator.accepts(&s[..end], last_dot) {
+                    return None;
+                }
             }
+            Some(end)
         } else {
             None
         }
     }
 
     // See "Atom" in RFC 5321, "atext" in RFC 5322
-    fn local_atom_allowed(c: char) -> bool {
+    pub(crate) fn local_atom_allowed(c: char) -> bool {
         match c {
             'a'..='z'
             | 'A'..='Z'
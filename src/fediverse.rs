@@ -0,0 +1,64 @@
+use std::ops::Range;
+
+use crate::domains::{find_authority_end, TldValidator};
+use crate::scanner::Scanner;
+
+/// Scan for Fediverse handles like `@alice@mastodon.social` starting from the trigger character,
+/// the leading `@`.
+///
+/// The username consists of ASCII letters, digits and `_`, with `.` and `-` allowed in the middle.
+/// The instance needs to be a domain name with at least one dot.
+pub struct FediverseScanner {
+    pub iri_parsing_enabled: bool,
+    pub tld_validator: Option<TldValidator>,
+}
+
+impl Scanner for FediverseScanner {
+    fn scan(&self, s: &str, at: usize) -> Option<Range<usize>> {
+        if let Some(c) = s[..at].chars().next_back() {
+            if c.is_alphanumeric() || "._-/&@#".contains(c) {
+                return None;
+            }
+        }
+
+        let username_start = at + 1;
+        let username_end = username_start + find_username_end(&s[username_start..])?;
+        if !s[username_end..].starts_with('@') {
+            return None;
+        }
+        let instance_start = username_end + 1;
+        let instance_end = instance_start + self.find_instance_end(&s[instance_start..])?;
+        Some(at..instance_end)
+    }
+}
+
+impl FediverseScanner {
+    fn find_instance_end(&self, s: &str) -> Option<usize> {
+        if s.starts_with('[') {
+            // IP literals are not supported
+            return None;
+        }
+        let (end, last_dot) = find_authority_end(s, false, true, false, self.iri_parsing_enabled);
+        let end = end?;
+        // Instances are domain names, not hosts like `localhost`
+        last_dot?;
+        if let Some(validator) = &self.tld_validator {
+            if !validator.accepts(&s[..end], last_dot) {
+                return None;
+            }
+        }
+        Some(end)
+    }
+}
+
+fn find_username_end(s: &str) -> Option<usize> {
+    let mut end = None;
+    for (i, c) in s.char_indices() {
+        match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => end = Some(i + 1),
+            '.' | '-' if end.is_some() => {}
+            _ => break,
+        }
+    }
+    end
+}
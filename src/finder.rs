@@ -1,10 +1,41 @@
+use std::borrow::Cow;
 use std::fmt;
+use std::io::BufRead;
 use std::iter::Peekable;
+use std::ops::Range;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::str;
+use std::sync::Arc;
+use std::vec;
 
 use memchr::{memchr, memchr2, memchr3};
+#[cfg(feature = "async")]
+use tokio::io::AsyncBufRead;
 
+use crate::ansi;
+use crate::components::Components;
+#[cfg(feature = "url")]
+use crate::convert::{self, ToUrlError};
+use crate::defang;
+use crate::domains::{host_kind, HostKind, TldValidator};
 use crate::email::EmailScanner;
+use crate::fediverse::FediverseScanner;
+#[cfg(feature = "homograph")]
+use crate::homograph::HostAnalysis;
+use crate::html;
+#[cfg(feature = "idna")]
+use crate::iri;
+#[cfg(feature = "markdown")]
+use crate::markdown;
+use crate::options::LinkFinderOptions;
+use crate::phone::{self, PhoneScanner};
+use crate::reference::{Reference, ReferenceRules, ReferenceScanner};
 use crate::scanner::Scanner;
+use crate::scheme::SchemePolicy;
+#[cfg(feature = "async")]
+use crate::stream::LinkStream;
+use crate::stream::{ChunkScanner, ReaderLinks};
+use crate::tag::{TagRules, TagScanner};
 use crate::url::{DomainScanner, UrlScanner};
 
 /// A link found in the input text.
@@ -14,6 +45,13 @@ pub struct Link<'t> {
     start: usize,
     end: usize,
     kind: LinkKind,
+    has_scheme: bool,
+    existing: bool,
+    defanged: bool,
+    /// Index of `text` within the input, non-zero for links in bytes
+    offset: usize,
+    /// The rules for turning references into URLs, only for `LinkKind::Reference`
+    reference_rules: Option<Arc<ReferenceRules>>,
 }
 
 impl<'t> Link<'t> {
@@ -32,7 +70,269 @@ impl<'t> Link<'t> {
     /// Get the link text as a `str`.
     #[inline]
     pub fn as_str(&self) -> &'t str {
-        &self.text[self.start..self.end]
+        &self.text[self.start - self.offset..self.end - self.offset]
+    }
+
+    /// The type of the link.
+    #[inline]
+    pub fn kind(&self) -> &LinkKind {
+        &self.kind
+    }
+
+    /// The components of the link such as scheme, host and path, as byte ranges within the input
+    /// text.
+    ///
+    /// ```
+    /// use linkify::LinkFinder;
+    ///
+    /// let input = "See https://example.org:8080/path?q=1#top";
+    /// let finder = LinkFinder::new();
+    /// let link = finder.links(input).next().unwrap();
+    /// let components = link.components();
+    ///
+    /// assert_eq!(Some("https"), components.scheme().map(|r| &input[r]));
+    /// assert_eq!(Some("example.org"), components.host().map(|r| &input[r]));
+    /// assert_eq!(Some("8080"), components.port().map(|r| &input[r]));
+    /// assert_eq!(Some("/path"), components.path().map(|r| &input[r]));
+    /// assert_eq!(Some("q=1"), components.query().map(|r| &input[r]));
+    /// assert_eq!(Some("top"), components.fragment().map(|r| &input[r]));
+    /// ```
+    ///
+    /// For defanged links like `hxxps://example[.]com`, the components are parsed with the
+    /// conventions replaced, but the ranges are still within the input text (e.g. the host is
+    /// `example[.]com`).
+    pub fn components(&self) -> Components {
+        if self.defanged {
+            defang::components(self.as_str(), self.start, &self.kind, self.has_scheme)
+        } else {
+            Components::parse(self.as_str(), self.start, &self.kind, self.has_scheme)
+        }
+    }
+
+    /// The kind of host of the link, e.g. a domain name or an IPv6 address.
+    ///
+    /// Returns `None` if the link doesn't have a host, e.g. for `file:///home` or opaque URIs like
+    /// `urn:isbn:0451450523`.
+    ///
+    /// ```
+    /// use linkify::{HostKind, LinkFinder};
+    ///
+    /// let finder = LinkFinder::new();
+    /// let link = finder.links("http://[2001:db8::1]:8080/path").next().unwrap();
+    /// assert_eq!(Some(HostKind::Ipv6), link.host_kind());
+    /// ```
+    pub fn host_kind(&self) -> Option<HostKind> {
+        let (host, _) = self.refanged_host()?;
+        Some(host_kind(&host))
+    }
+
+    /// Analyze the host of the link for homograph spoofing, e.g. `аpple.com` with a Cyrillic `а`
+    /// (which can be found with [`LinkFinder::url_can_be_iri`]). See [`HostAnalysis`].
+    ///
+    /// Returns `None` if the link doesn't have a host or if the host is not a domain name (e.g. an
+    /// IP address).
+    #[cfg(feature = "homograph")]
+    pub fn host_analysis(&self) -> Option<HostAnalysis> {
+        let (host, offsets) = self.refanged_host()?;
+        match host_kind(&host) {
+            HostKind::Domain => Some(HostAnalysis::analyze(&host, |i| offsets[i])),
+            _ => None,
+        }
+    }
+
+    /// The host with defang conventions replaced, and the offsets that map each byte index of it
+    /// (and its length) to the index in the input.
+    fn refanged_host(&self) -> Option<(Cow<'t, str>, Vec<usize>)> {
+        let range = self.components().host()?;
+        let host = &self.text[range.start - self.offset..range.end - self.offset];
+        match defang::refang(host, range.start).filter(|_| self.defanged) {
+            Some((refanged, offsets)) => Some((Cow::Owned(refanged), offsets)),
+            None => Some((Cow::Borrowed(host), (range.start..=range.end).collect())),
+        }
+    }
+
+    /// The link in ASCII form, e.g. for storing it: the host is converted to Punycode using IDNA
+    /// (e.g. `xn--bcher-kva.de` for `bücher.de`) and other non-ASCII characters are
+    /// percent-encoded as UTF-8 as described in RFC 3987, section 3.1. For emails, only the domain
+    /// is converted.
+    ///
+    /// Returns `None` for kinds of links other than URLs and emails, or if the host can't be
+    /// converted.
+    ///
+    /// ```
+    /// use linkify::LinkFinder;
+    ///
+    /// let finder = LinkFinder::new();
+    /// let link = finder.links("https://bücher.de/straße?q=ä").next().unwrap();
+    /// assert_eq!(
+    ///     Some("https://xn--bcher-kva.de/stra%C3%9Fe?q=%C3%A4".to_string()),
+    ///     link.to_ascii_url()
+    /// );
+    /// ```
+    #[cfg(feature = "idna")]
+    pub fn to_ascii_url(&self) -> Option<String> {
+        match self.kind {
+            LinkKind::Url | LinkKind::Email => iri::to_ascii(self),
+            _ => None,
+        }
+    }
+
+    /// The link in Unicode form for displaying it, the reverse of
+    /// [`to_ascii_url`](Link::to_ascii_url): Punycode labels of the host are converted to Unicode
+    /// and percent-encoded UTF-8 characters are decoded as described in RFC 3987, section 3.2.
+    ///
+    /// Percent-encoded ASCII characters, invalid UTF-8 and invisible characters such as
+    /// bidirectional controls stay encoded, so that the result means the same as the link.
+    /// Returns `None` for kinds of links other than URLs and emails.
+    ///
+    /// ```
+    /// use linkify::LinkFinder;
+    ///
+    /// let finder = LinkFinder::new();
+    /// let link = finder.links("https://xn--bcher-kva.de/stra%C3%9Fe%20x").next().unwrap();
+    /// assert_eq!(
+    ///     Some("https://bücher.de/straße%20x".to_string()),
+    ///     link.to_unicode_display()
+    /// );
+    /// ```
+    #[cfg(feature = "idna")]
+    pub fn to_unicode_display(&self) -> Option<String> {
+        match self.kind {
+            LinkKind::Url | LinkKind::Email => Some(iri::to_unicode(self)),
+            _ => None,
+        }
+    }
+
+    /// Convert the link to a [`url::Url`], adding an implied scheme where needed: `https://` for
+    /// URLs without a scheme (e.g. `example.org`), `mailto:` for emails and `tel:` for phone
+    /// numbers. Fediverse handles are converted to the URL of the profile and references to the
+    /// URL from the template (see [`LinkFinder::reference_rules`]).
+    ///
+    /// Returns an error if the kind of link doesn't have a URL (e.g. mentions), or if the link is
+    /// not a valid URL according to the WHATWG URL Standard (which is stricter than the link
+    /// finder).
+    ///
+    /// ```
+    /// use linkify::LinkFinder;
+    ///
+    /// let mut finder = LinkFinder::new();
+    /// finder.url_must_have_scheme(false);
+    /// let links: Vec<_> = finder.links("See example.org/a or mail a@example.org").collect();
+    /// assert_eq!("https://example.org/a", links[0].to_url().unwrap().as_str());
+    /// assert_eq!("mailto:a@example.org", links[1].to_url().unwrap().as_str());
+    /// ```
+    #[cfg(feature = "url")]
+    pub fn to_url(&self) -> Result<::url::Url, ToUrlError> {
+        convert::to_url(self)
+    }
+
+    /// Whether the link is an existing link in the input, e.g. the destination of `[text](url)`
+    /// in Markdown. These are only returned with [`LinkFinder::existing_links`].
+    #[inline]
+    pub fn is_existing(&self) -> bool {
+        self.existing
+    }
+
+    /// For phone numbers, the number with only the digits and a leading `+` if any, for use in a
+    /// `tel:` URI. E.g. `+15550100199` for `+1 (555) 010-0199`. Returns `None` for other kinds of
+    /// links.
+    pub fn phone_number(&self) -> Option<String> {
+        match self.kind {
+            LinkKind::Phone => Some(phone::normalize(self.as_str())),
+            _ => None,
+        }
+    }
+
+    /// Whether the link is defanged, e.g. `hxxps://example[.]com`. Links are only found defanged
+    /// with [`LinkFinder::defanged`], and only if the scheme or host uses the conventions.
+    #[inline]
+    pub fn is_defanged(&self) -> bool {
+        self.defanged
+    }
+
+    /// The link with defang conventions replaced, e.g. `https://example.com` for
+    /// `hxxps://example[.]com`. See [`LinkFinder::defanged`].
+    ///
+    /// Only the scheme and host (and for emails the `@` before it) are refanged, so the path of
+    /// e.g. `hxxps://example[.]com/Foo_(dot)` stays the same. Links that are not
+    /// [defanged](Link::is_defanged) are returned as they are.
+    pub fn refanged(&self) -> String {
+        refanged(
+            self.as_str(),
+            self.start,
+            &self.kind,
+            self.has_scheme,
+            self.defanged,
+        )
+    }
+
+    /// For references, the parsed parts such as the issue number. Returns `None` for other kinds
+    /// of links.
+    ///
+    /// ```
+    /// use linkify::{LinkFinder, LinkKind, Reference};
+    ///
+    /// let mut finder = LinkFinder::new();
+    /// finder.kinds(&[LinkKind::Reference]);
+    /// let link = finder.links("Fixed in robinst/linkify#42").next().unwrap();
+    /// assert_eq!(
+    ///     Some(Reference::Issue {
+    ///         owner: Some("robinst"),
+    ///         repo: Some("linkify"),
+    ///         number: 42
+    ///     }),
+    ///     link.reference()
+    /// );
+    /// ```
+    pub fn reference(&self) -> Option<Reference<'t>> {
+        match self.kind {
+            LinkKind::Reference => Reference::parse(self.as_str()),
+            _ => None,
+        }
+    }
+
+    /// For references, the URL according to the templates of
+    /// [`LinkFinder::reference_rules`]. Returns `None` for other kinds of links, or if there's no
+    /// template for the reference.
+    pub fn reference_url(&self) -> Option<String> {
+        self.reference_rules.as_ref()?.url(&self.reference()?)
+    }
+
+    /// Whether the link starts with a scheme (as opposed to e.g. `example.org`).
+    pub(crate) fn has_scheme(&self) -> bool {
+        self.has_scheme
+    }
+}
+
+/// A link that owns its text, e.g. from [`LinkFinder::links_reader`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct OwnedLink {
+    text: String,
+    start: usize,
+    end: usize,
+    kind: LinkKind,
+    has_scheme: bool,
+    existing: bool,
+    defanged: bool,
+}
+
+impl OwnedLink {
+    /// The start index of the link within the input.
+    #[inline]
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The end index of the link.
+    #[inline]
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Get the link text as a `str`.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.text
     }
 
     /// The type of the link.
@@ -40,16 +340,122 @@ impl<'t> Link<'t> {
     pub fn kind(&self) -> &LinkKind {
         &self.kind
     }
+
+    /// The components of the link, as byte ranges within the input, see [`Link::components`].
+    pub fn components(&self) -> Components {
+        if self.defanged {
+            defang::components(&self.text, self.start, &self.kind, self.has_scheme)
+        } else {
+            Components::parse(&self.text, self.start, &self.kind, self.has_scheme)
+        }
+    }
+
+    /// Whether the link is an existing link in the input, see [`Link::is_existing`].
+    #[inline]
+    pub fn is_existing(&self) -> bool {
+        self.existing
+    }
+
+    /// Whether the link is defanged, see [`Link::is_defanged`].
+    #[inline]
+    pub fn is_defanged(&self) -> bool {
+        self.defanged
+    }
+
+    /// The link with defang conventions replaced, see [`Link::refanged`].
+    pub fn refanged(&self) -> String {
+        refanged(
+            &self.text,
+            self.start,
+            &self.kind,
+            self.has_scheme,
+            self.defanged,
+        )
+    }
+
+    /// Create a link from deserialized parts, the scheme is detected from the text. Whether it's
+    /// defanged is checked again too.
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_parts(
+        text: String,
+        start: usize,
+        kind: LinkKind,
+        existing: bool,
+        defanged: bool,
+    ) -> OwnedLink {
+        let has_scheme = kind == LinkKind::Url && scheme_length(&text).is_some();
+        let defanged = defanged && defang::is_defanged(&text, &kind, has_scheme);
+        OwnedLink {
+            end: start + text.len(),
+            text,
+            start,
+            kind,
+            has_scheme,
+            existing,
+            defanged,
+        }
+    }
+
+    /// Move the link by the specified number of bytes, for links found in part of the input.
+    pub(crate) fn shift(mut self, offset: usize) -> OwnedLink {
+        self.start += offset;
+        self.end += offset;
+        self
+    }
+}
+
+impl<'t> From<Link<'t>> for OwnedLink {
+    fn from(link: Link<'t>) -> OwnedLink {
+        OwnedLink {
+            text: link.as_str().to_string(),
+            start: link.start,
+            end: link.end,
+            kind: link.kind,
+            has_scheme: link.has_scheme,
+            existing: link.existing,
+            defanged: link.defanged,
+        }
+    }
+}
+
+/// The text of a link with the defang conventions in the scheme and host replaced.
+fn refanged(text: &str, start: usize, kind: &LinkKind, has_scheme: bool, defanged: bool) -> String {
+    match defang::refang_link(text, start, kind, has_scheme).filter(|_| defanged) {
+        Some((refanged, _)) => refanged,
+        None => text.to_string(),
+    }
 }
 
 /// The type of link that was found.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 #[non_exhaustive]
 pub enum LinkKind {
     /// URL links like "http://example.org".
     Url,
     /// E-mail links like "foo@example.org"
     Email,
+    /// Phone numbers like "+41 44 668 18 00" or "(555) 123-4567", only found if enabled with
+    /// [`LinkFinder::kinds`]. See [`Link::phone_number`] for the number to use in a `tel:` URI.
+    Phone,
+    /// Mentions of users like "@alice", only found if enabled with [`LinkFinder::kinds`]. See
+    /// [`LinkFinder::mention_rules`] for which names are allowed.
+    Mention,
+    /// Hashtags like "#release-notes", only found if enabled with [`LinkFinder::kinds`]. See
+    /// [`LinkFinder::hashtag_rules`] for which names are allowed.
+    Hashtag,
+    /// Fediverse handles like "@alice@mastodon.social", only found if enabled with
+    /// [`LinkFinder::kinds`]. The [`components`](Link::components) are the username (as the local
+    /// part) and the instance (as the host).
+    FediverseHandle,
+    /// References to issues, tickets or commits like "#123", "owner/repo#123", "PROJ-1234" or
+    /// "3fcb00f", only found if enabled with [`LinkFinder::kinds`]. See [`Link::reference`] for
+    /// the parsed parts and [`LinkFinder::reference_rules`] for configuration.
+    Reference,
 }
 
 /// Span within the input text.
@@ -62,6 +468,10 @@ pub struct Span<'t> {
     start: usize,
     end: usize,
     kind: Option<LinkKind>,
+    has_scheme: bool,
+    existing: bool,
+    defanged: bool,
+    reference_rules: Option<Arc<ReferenceRules>>,
 }
 
 impl<'t> Span<'t> {
@@ -90,16 +500,141 @@ impl<'t> Span<'t> {
     pub fn kind(&self) -> Option<&LinkKind> {
         self.kind.as_ref()
     }
+
+    /// Whether the span is an existing link in the input, see [`Link::is_existing`].
+    #[inline]
+    pub fn is_existing(&self) -> bool {
+        self.existing
+    }
+
+    /// Whether the span is a defanged link, see [`Link::is_defanged`].
+    #[inline]
+    pub fn is_defanged(&self) -> bool {
+        self.defanged
+    }
+
+    /// For references, the URL according to the templates, see [`Link::reference_url`].
+    pub fn reference_url(&self) -> Option<String> {
+        self.link()?.reference_url()
+    }
+
+    /// The link of the span, or `None` if the span represents plain text.
+    pub(crate) fn link(&self) -> Option<Link<'t>> {
+        self.kind.map(|kind| Link {
+            text: self.text,
+            start: self.start,
+            end: self.end,
+            kind,
+            has_scheme: self.has_scheme,
+            existing: self.existing,
+            defanged: self.defanged,
+            offset: 0,
+            reference_rules: self.reference_rules.clone(),
+        })
+    }
 }
 
 /// A configured link finder.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LinkFinder {
     email: bool,
     email_domain_must_have_dot: bool,
     url: bool,
     url_must_have_scheme: bool,
     url_can_be_iri: bool,
+    phone: bool,
+    fediverse: bool,
+    mention: bool,
+    hashtag: bool,
+    reference: bool,
+    mention_rules: Arc<TagRules>,
+    hashtag_rules: Arc<TagRules>,
+    reference_rules: Arc<ReferenceRules>,
+    scheme_policy: Arc<SchemePolicy>,
+    tld_validator: Option<TldValidator>,
+    input_format: InputFormat,
+    existing_links: bool,
+    defanged: bool,
+}
+
+/// The format of the input text, see [`LinkFinder::input_format`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum InputFormat {
+    /// Plain text, links are found anywhere (the default).
+    Plain,
+    /// Markdown, links are only found in prose. Code spans, code blocks and existing links such as
+    /// `[text](url)`, `<url>` or reference links are skipped.
+    #[cfg(feature = "markdown")]
+    Markdown,
+    /// HTML, links are only found in text content. Tags, comments, the contents of `<script>` and
+    /// `<style>` elements as well as existing links (`<a>`) and `<code>` and `<pre>` elements are
+    /// skipped. Entities like `&amp;` are decoded for finding links, e.g. in
+    /// `http://example.org/?a=1&amp;b=2`.
+    Html,
+    /// Text with ANSI escape sequences, e.g. output of a command in a terminal. Escape sequences
+    /// such as colors (`\x1b[31m`) are skipped when finding links, so that links are found in the
+    /// text as it's displayed. The text of existing OSC 8 hyperlinks is skipped too.
+    ///
+    /// Links start and end at visible text, but can contain escape sequences in between.
+    Ansi,
+}
+
+/// Part of the input text to find links in.
+#[derive(Debug)]
+pub(crate) enum Segment {
+    /// Scan for links in this range of the input text.
+    Text(Range<usize>),
+    /// Scan for links in decoded text (e.g. with HTML entities replaced). The offsets map each
+    /// byte index of the decoded text (and its length) to the index in the input text.
+    Mapped { text: String, offsets: Vec<usize> },
+    /// An existing link in the input, e.g. from markup.
+    Existing {
+        range: Range<usize>,
+        kind: LinkKind,
+        has_scheme: bool,
+    },
+}
+
+impl Segment {
+    /// A segment for the destination of an existing link in the input text.
+    pub(crate) fn existing(text: &str, range: Range<usize>) -> Segment {
+        let destination = &text[range.clone()];
+        let has_scheme = scheme_length(destination).is_some();
+        let kind = if !has_scheme && destination.contains('@') && !destination.contains('/') {
+            LinkKind::Email
+        } else {
+            LinkKind::Url
+        };
+        Segment::Existing {
+            range,
+            kind,
+            has_scheme,
+        }
+    }
+}
+
+/// The length of the scheme if the text starts with a scheme followed by `:` (as in CommonMark
+/// autolinks, schemes have 2 to 32 characters).
+pub(crate) fn scheme_length(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    if !bytes.first()?.is_ascii_alphabetic() {
+        return None;
+    }
+    let len = bytes
+        .iter()
+        .take_while(|&&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'.' || b == b'-')
+        .count();
+    if (2..=32).contains(&len) && bytes.get(len) == Some(&b':') {
+        Some(len)
+    } else {
+        None
+    }
 }
 
 type TriggerFinder = dyn Fn(&[u8]) -> Option<usize>;
@@ -107,12 +642,27 @@ type TriggerFinder = dyn Fn(&[u8]) -> Option<usize>;
 /// Iterator for finding links.
 pub struct Links<'t> {
     text: &'t str,
+    /// Index of `text` within the input, see [`ByteLinks`]
+    offset: usize,
     rewind: usize,
+    /// Whether `rewind` is the end of the previous link, so there's no lookbehind before it
+    after_link: bool,
+    segment: Option<Segment>,
+    segments: vec::IntoIter<Segment>,
+    /// Whether to trim escape sequences from the end of links, see [`InputFormat::Ansi`]
+    trim_escapes: bool,
+    /// Whether links are found in refanged text, see [`LinkFinder::defanged`]
+    refang: bool,
 
     trigger_finder: Box<TriggerFinder>,
-    email_scanner: EmailScanner,
+    email_scanner: Option<EmailScanner>,
     url_scanner: UrlScanner,
     domain_scanner: DomainScanner,
+    fediverse_scanner: Option<FediverseScanner>,
+    mention_scanner: Option<TagScanner>,
+    hashtag_scanner: Option<TagScanner>,
+    phone_scanner: Option<PhoneScanner>,
+    reference_scanner: Option<ReferenceScanner>,
 }
 
 /// Iterator over spans.
@@ -134,6 +684,19 @@ impl LinkFinder {
             url: true,
             url_must_have_scheme: true,
             url_can_be_iri: true,
+            phone: false,
+            fediverse: false,
+            mention: false,
+            hashtag: false,
+            reference: false,
+            mention_rules: Arc::new(TagRules::mention()),
+            hashtag_rules: Arc::new(TagRules::hashtag()),
+            reference_rules: Arc::new(ReferenceRules::new()),
+            scheme_policy: Arc::new(SchemePolicy::new()),
+            tld_validator: None,
+            input_format: InputFormat::Plain,
+            existing_links: false,
+            defanged: false,
         }
     }
 
@@ -163,31 +726,357 @@ impl LinkFinder {
         self
     }
 
+    /// Set the policy for which URL schemes are found and how their authority is parsed.
+    ///
+    /// By default, URLs with any scheme are found, see [`SchemePolicy`] for details.
+    pub fn scheme_policy(&mut self, policy: SchemePolicy) -> &mut LinkFinder {
+        self.scheme_policy = Arc::new(policy);
+        self
+    }
+
+    /// Set a function for checking the top-level domain (TLD) of URLs without a scheme and of
+    /// emails, e.g. with `example.com` the function is called with `com`. If the function returns
+    /// `false`, the link is not found.
+    ///
+    /// This is useful with `url_must_have_scheme(false)`, where otherwise things like `file.txt`
+    /// are found as URLs. With the `tlds` feature enabled, `linkify::is_known_tld` can be used to
+    /// only allow known TLDs. Note that many file extensions are real TLDs too (e.g. `md`, `rs`,
+    /// `py` or `zip`), so file names like `README.md` are still found with it.
+    ///
+    /// Note that domains without a dot (e.g. `root@localhost`) and IPv4 addresses are not checked.
+    ///
+    /// ```
+    /// use linkify::LinkFinder;
+    ///
+    /// let mut finder = LinkFinder::new();
+    /// finder.url_must_have_scheme(false);
+    /// finder.tld_validator(|tld| matches!(tld, "com" | "org"));
+    ///
+    /// let links: Vec<_> = finder.links("README.md and example.org").collect();
+    /// assert_eq!(1, links.len());
+    /// assert_eq!("example.org", links[0].as_str());
+    /// ```
+    pub fn tld_validator<F>(&mut self, validator: F) -> &mut LinkFinder
+    where
+        F: Fn(&str) -> bool + Send + Sync + RefUnwindSafe + UnwindSafe + 'static,
+    {
+        self.tld_validator = Some(TldValidator(Arc::new(validator)));
+        self
+    }
+
+    /// Set the format of the input text, defaults to `InputFormat::Plain`.
+    ///
+    /// With a markup format, links are only found in the text content, not in code or in existing
+    /// links. The returned links still refer to the original input text.
+    ///
+    /// ```
+    /// # #[cfg(feature = "markdown")] {
+    /// use linkify::{InputFormat, LinkFinder};
+    ///
+    /// let input = "See http://example.org and [this](http://example.com), not `http://a.b`";
+    /// let mut finder = LinkFinder::new();
+    /// finder.input_format(InputFormat::Markdown);
+    ///
+    /// let links: Vec<_> = finder.links(input).collect();
+    /// assert_eq!(1, links.len());
+    /// assert_eq!("http://example.org", links[0].as_str());
+    /// # }
+    /// ```
+    pub fn input_format(&mut self, input_format: InputFormat) -> &mut LinkFinder {
+        self.input_format = input_format;
+        self
+    }
+
+    /// Set whether existing links in markup should be returned as well, defaults to `false`.
+    ///
+    /// With a markup [`input_format`](LinkFinder::input_format), the destinations of existing
+    /// links (e.g. `url` in `[text](url)` in Markdown or `<a href="url">` in HTML) are not
+    /// returned by default. If this is enabled, they are returned with [`Link::is_existing`]
+    /// returning `true`. Note that they are returned as they are in the input, without checking
+    /// that they are valid links.
+    pub fn existing_links(&mut self, existing_links: bool) -> &mut LinkFinder {
+        self.existing_links = existing_links;
+        self
+    }
+
+    /// Set whether to find defanged links like `hxxps://example[.]com` or `user[at]example[dot]com`
+    /// that are written so that they can't be clicked, e.g. in threat intelligence reports.
+    /// Defaults to `false`.
+    ///
+    /// The following conventions are recognized: `hxxp`, `hxxps` and `fxp` for schemes, `[.]` and
+    /// `[dot]` for `.`, `[at]` and `[@]` for `@`, as well as `[:]` and `[://]`. Instead of square
+    /// brackets, parentheses and curly braces work too. Only links that use them in the scheme or
+    /// host are [defanged](Link::is_defanged). Links are returned as they are in the input, use
+    /// [`Link::refanged`] to get the actual link.
+    ///
+    /// ```
+    /// use linkify::LinkFinder;
+    ///
+    /// let mut finder = LinkFinder::new();
+    /// finder.defanged(true);
+    /// let link = finder.links("Seen at hxxps://evil[.]example[.]com/x").next().unwrap();
+    /// assert_eq!("hxxps://evil[.]example[.]com/x", link.as_str());
+    /// assert_eq!("https://evil.example.com/x", link.refanged());
+    /// ```
+    pub fn defanged(&mut self, defanged: bool) -> &mut LinkFinder {
+        self.defanged = defanged;
+        self
+    }
+
     /// Restrict the kinds of links that should be found to the specified ones.
+    ///
+    /// By default, URLs and emails are found. Other kinds such as phone numbers, mentions or
+    /// references are only found if their kind is included:
+    ///
+    /// ```
+    /// use linkify::{LinkFinder, LinkKind};
+    ///
+    /// let mut finder = LinkFinder::new();
+    /// finder.kinds(&[LinkKind::Url, LinkKind::Email, LinkKind::Phone]);
+    /// let links: Vec<_> = finder.links("Call +1 (555) 010-0199 or see http://example.org").collect();
+    /// assert_eq!(LinkKind::Phone, *links[0].kind());
+    /// assert_eq!("+1 (555) 010-0199", links[0].as_str());
+    /// assert_eq!(Some("+15550100199".to_string()), links[0].phone_number());
+    /// ```
     pub fn kinds(&mut self, kinds: &[LinkKind]) -> &mut LinkFinder {
         self.email = false;
         self.url = false;
+        self.phone = false;
+        self.fediverse = false;
+        self.mention = false;
+        self.hashtag = false;
+        self.reference = false;
         for kind in kinds {
             match *kind {
                 LinkKind::Email => self.email = true,
                 LinkKind::Url => self.url = true,
+                LinkKind::Phone => self.phone = true,
+                LinkKind::FediverseHandle => self.fediverse = true,
+                LinkKind::Mention => self.mention = true,
+                LinkKind::Hashtag => self.hashtag = true,
+                LinkKind::Reference => self.reference = true,
             }
         }
         self
     }
 
+    /// Set the rules for the names of mentions like `@alice`, defaults to [`TagRules::mention`].
+    ///
+    /// Mentions are only found if `LinkKind::Mention` is enabled with [`kinds`](LinkFinder::kinds).
+    /// Something like `alice@example.org` is an email (if enabled) and not a mention.
+    ///
+    /// ```
+    /// use linkify::{LinkFinder, LinkKind, TagRules};
+    ///
+    /// let mut finder = LinkFinder::new();
+    /// finder.kinds(&[LinkKind::Email, LinkKind::Mention]);
+    /// finder.mention_rules(TagRules::mention().max_length(15));
+    ///
+    /// let links: Vec<_> = finder.links("@alice, mail alice@example.org").collect();
+    /// assert_eq!("@alice", links[0].as_str());
+    /// assert_eq!(LinkKind::Mention, *links[0].kind());
+    /// assert_eq!(LinkKind::Email, *links[1].kind());
+    /// ```
+    pub fn mention_rules(&mut self, rules: TagRules) -> &mut LinkFinder {
+        self.mention_rules = Arc::new(rules);
+        self
+    }
+
+    /// Set the rules for the names of hashtags like `#release-notes`, defaults to
+    /// [`TagRules::hashtag`].
+    ///
+    /// Hashtags are only found if `LinkKind::Hashtag` is enabled with
+    /// [`kinds`](LinkFinder::kinds).
+    pub fn hashtag_rules(&mut self, rules: TagRules) -> &mut LinkFinder {
+        self.hashtag_rules = Arc::new(rules);
+        self
+    }
+
+    /// Set the rules for references like `#123` or `PROJ-1234`, defaults to
+    /// [`ReferenceRules::new`].
+    ///
+    /// References are only found if `LinkKind::Reference` is enabled with
+    /// [`kinds`](LinkFinder::kinds). With URL templates, references are rendered as links by
+    /// [`HtmlRenderer`](crate::html::HtmlRenderer), see [`Link::reference_url`].
+    ///
+    /// ```
+    /// use linkify::{LinkFinder, LinkKind, ReferenceRules};
+    ///
+    /// let mut finder = LinkFinder::new();
+    /// finder.kinds(&[LinkKind::Reference]);
+    /// finder.reference_rules(
+    ///     ReferenceRules::new()
+    ///         .repository("robinst", "linkify")
+    ///         .issue_url("https://github.com/{owner}/{repo}/issues/{n}"),
+    /// );
+    ///
+    /// let link = finder.links("Fixes #42").next().unwrap();
+    /// assert_eq!("#42", link.as_str());
+    /// assert_eq!(
+    ///     Some("https://github.com/robinst/linkify/issues/42".to_string()),
+    ///     link.reference_url()
+    /// );
+    /// ```
+    pub fn reference_rules(&mut self, rules: ReferenceRules) -> &mut LinkFinder {
+        self.reference_rules = Arc::new(rules);
+        self
+    }
+
+    /// The options of this finder, e.g. for saving them in a config file with the `serde`
+    /// feature. Use `LinkFinder::from` to create a finder from options again.
+    ///
+    /// The TLD validator is not part of the options (it's a function), so a finder created from
+    /// them again doesn't have one.
+    pub fn options(&self) -> LinkFinderOptions {
+        let kinds = [
+            (self.url, LinkKind::Url),
+            (self.email, LinkKind::Email),
+            (self.phone, LinkKind::Phone),
+            (self.fediverse, LinkKind::FediverseHandle),
+            (self.mention, LinkKind::Mention),
+            (self.hashtag, LinkKind::Hashtag),
+            (self.reference, LinkKind::Reference),
+        ];
+        LinkFinderOptions {
+            kinds: kinds
+                .iter()
+                .filter(|(enabled, _)| *enabled)
+                .map(|(_, kind)| *kind)
+                .collect(),
+            url_must_have_scheme: self.url_must_have_scheme,
+            url_can_be_iri: self.url_can_be_iri,
+            email_domain_must_have_dot: self.email_domain_must_have_dot,
+            input_format: self.input_format,
+            existing_links: self.existing_links,
+            defanged: self.defanged,
+            mention_rules: (*self.mention_rules).clone(),
+            hashtag_rules: (*self.hashtag_rules).clone(),
+            reference_rules: (*self.reference_rules).clone(),
+            scheme_policy: (*self.scheme_policy).clone(),
+        }
+    }
+
+    /// Whether found links can contain spaces, which is the case for phone numbers.
+    pub(crate) fn links_can_contain_spaces(&self) -> bool {
+        self.phone
+    }
+
+    /// Defang the URLs and emails in the text for safe display, e.g. `https://example.com` becomes
+    /// `hxxps://example[.]com` and `user@example.com` becomes `user[at]example[.]com`.
+    ///
+    /// Only the scheme and the dots in the host are changed, the rest of the text stays the same.
+    /// Links that are already defanged (when found with [`defanged`](LinkFinder::defanged)) are
+    /// written in the same way.
+    ///
+    /// ```
+    /// use linkify::LinkFinder;
+    ///
+    /// let finder = LinkFinder::new();
+    /// assert_eq!(
+    ///     "Go to hxxps://example[.]com/path or mail user[at]example[.]com",
+    ///     finder.defang("Go to https://example.com/path or mail user@example.com")
+    /// );
+    /// ```
+    pub fn defang(&self, text: &str) -> String {
+        self.replace_all(text, |link| match link.kind() {
+            LinkKind::Url | LinkKind::Email => Cow::Owned(defang::defang_link(link)),
+            _ => Cow::Borrowed(link.as_str()),
+        })
+        .into_owned()
+    }
+
     /// Find links in the specified input text.
     ///
     /// Returns an `Iterator` which only scans when `next` is called (lazy).
     pub fn links<'t>(&self, text: &'t str) -> Links<'t> {
-        Links::new(
-            text,
-            self.url,
-            self.url_must_have_scheme,
-            self.email,
-            self.email_domain_must_have_dot,
-            self.url_can_be_iri,
-        )
+        Links::new(text, self)
+    }
+
+    /// Find links in the specified input bytes, e.g. log files that are mostly UTF-8 but can
+    /// contain invalid sequences.
+    ///
+    /// Invalid UTF-8 sequences are treated like characters that can't be part of a link, so links
+    /// are only found within the valid parts. The start and end of the returned links are byte
+    /// indexes into the input. With a markup [`input_format`](LinkFinder::input_format), each
+    /// valid part is parsed separately.
+    ///
+    /// Returns an `Iterator` which only scans when `next` is called (lazy).
+    ///
+    /// ```
+    /// use linkify::LinkFinder;
+    ///
+    /// let input = b"\xff\xfehttp://example.org\xff and foo@example.org";
+    /// let finder = LinkFinder::new();
+    /// let links: Vec<_> = finder.links_bytes(input).collect();
+    ///
+    /// assert_eq!(2, links.len());
+    /// assert_eq!("http://example.org", links[0].as_str());
+    /// assert_eq!(2, links[0].start());
+    /// assert_eq!(b"foo@example.org", &input[links[1].start()..links[1].end()]);
+    /// ```
+    pub fn links_bytes<'t>(&self, bytes: &'t [u8]) -> ByteLinks<'t> {
+        ByteLinks {
+            bytes,
+            position: 0,
+            finder: self.clone(),
+            links: None,
+        }
+    }
+
+    /// Find links in the input from the specified reader, without reading all of it into memory.
+    ///
+    /// The input is read in chunks and scanned up to the last ASCII whitespace of each chunk, as
+    /// links can't contain whitespace. So links that span across chunks are found as usual, and
+    /// the returned links have byte offsets into the whole input. As with
+    /// [`links_bytes`](LinkFinder::links_bytes), the input doesn't need to be valid UTF-8.
+    ///
+    /// Note that the input is always treated as plain text, the
+    /// [`input_format`](LinkFinder::input_format) is not used.
+    ///
+    /// Returns an `Iterator` which only reads when `next` is called (lazy). An error from the
+    /// reader is returned as an item, after which the iterator ends.
+    ///
+    /// ```
+    /// use linkify::LinkFinder;
+    ///
+    /// let input = "See http://example.org and foo@example.org\n".repeat(1000);
+    /// let finder = LinkFinder::new();
+    /// let links = finder
+    ///     .links_reader(input.as_bytes())
+    ///     .collect::<std::io::Result<Vec<_>>>()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(2000, links.len());
+    /// assert_eq!("foo@example.org", links[1999].as_str());
+    /// assert_eq!(input.len() - 16, links[1999].start());
+    /// ```
+    pub fn links_reader<R: BufRead>(&self, reader: R) -> ReaderLinks<R> {
+        ReaderLinks::new(reader, ChunkScanner::new(self))
+    }
+
+    /// Find links in the input from the specified async reader, as a `Stream` of links.
+    ///
+    /// This works the same way as [`links_reader`](LinkFinder::links_reader), but for a tokio
+    /// `AsyncBufRead`. To use an `AsyncRead`, wrap it in a `tokio::io::BufReader`. Readers that
+    /// are not `Unpin` can be pinned with `Box::pin`.
+    ///
+    /// ```
+    /// # async fn example(body: impl tokio::io::AsyncBufRead + Unpin) -> std::io::Result<()> {
+    /// use futures_util::StreamExt;
+    /// use linkify::LinkFinder;
+    ///
+    /// let finder = LinkFinder::new();
+    /// let mut links = finder.link_stream(body);
+    /// while let Some(link) = links.next().await {
+    ///     let link = link?;
+    ///     println!("{} at {}", link.as_str(), link.start());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn link_stream<R: AsyncBufRead + Unpin>(&self, reader: R) -> LinkStream<R> {
+        LinkStream::new(reader, ChunkScanner::new(self))
     }
 
     /// Iterate over spans in the specified input text.
@@ -206,6 +1095,79 @@ impl LinkFinder {
             links: self.links(text).peekable(),
         }
     }
+
+    /// Replace the links in the text with the result of the function, e.g. to turn them into
+    /// links in some markup language.
+    ///
+    /// The function can return the text of the link (`Cow::Borrowed(link.as_str())`) to keep it
+    /// as it is. If no link is changed, the input text is returned without allocating.
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use linkify::{LinkFinder, LinkKind};
+    ///
+    /// let finder = LinkFinder::new();
+    /// let result = finder.replace_all("See http://example.org or mail a@example.org", |link| {
+    ///     match link.kind() {
+    ///         LinkKind::Url => Cow::Owned(format!("[{0}]({0})", link.as_str())),
+    ///         _ => Cow::Borrowed(link.as_str()),
+    ///     }
+    /// });
+    /// assert_eq!(
+    ///     "See [http://example.org](http://example.org) or mail a@example.org",
+    ///     result
+    /// );
+    ///
+    /// let unchanged = finder.replace_all("mail a@example.org", |link| link.as_str().into());
+    /// assert!(matches!(unchanged, Cow::Borrowed(_)));
+    /// ```
+    pub fn replace_all<'t, F>(&self, text: &'t str, mut replacement: F) -> Cow<'t, str>
+    where
+        F: for<'l> FnMut(&'l Link<'t>) -> Cow<'l, str>,
+    {
+        let mut result = String::new();
+        let mut changed = false;
+        let mut last = 0;
+        for link in self.links(text) {
+            let replaced = replacement(&link);
+            if replaced == link.as_str() {
+                continue;
+            }
+            if !changed {
+                result.reserve(text.len());
+                changed = true;
+            }
+            result.push_str(&text[last..link.start()]);
+            result.push_str(&replaced);
+            last = link.end();
+        }
+        if !changed {
+            return Cow::Borrowed(text);
+        }
+        result.push_str(&text[last..]);
+        Cow::Owned(result)
+    }
+
+    /// Replace the links in the text with the result of the function like
+    /// [`replace_all`](LinkFinder::replace_all), but write the result to the specified writer.
+    pub fn replace_all_to<'t, W, F>(
+        &self,
+        text: &'t str,
+        out: &mut W,
+        mut replacement: F,
+    ) -> fmt::Result
+    where
+        W: fmt::Write,
+        F: for<'l> FnMut(&'l Link<'t>) -> Cow<'l, str>,
+    {
+        let mut last = 0;
+        for link in self.links(text) {
+            out.write_str(&text[last..link.start()])?;
+            out.write_str(&replacement(&link))?;
+            last = link.end();
+        }
+        out.write_str(&text[last..])
+    }
 }
 
 impl Default for LinkFinder {
@@ -215,70 +1177,247 @@ impl Default for LinkFinder {
 }
 
 impl<'t> Links<'t> {
-    fn new(
-        text: &'t str,
-        url: bool,
-        url_must_have_scheme: bool,
-        email: bool,
-        email_domain_must_have_dot: bool,
-        iri_parsing_enabled: bool,
-    ) -> Links<'t> {
+    fn new(text: &'t str, finder: &LinkFinder) -> Links<'t> {
+        // Plain text is a single text segment, other formats are split into segments first
+        let segments: Option<Vec<Segment>> = match finder.input_format {
+            InputFormat::Plain => None,
+            #[cfg(feature = "markdown")]
+            InputFormat::Markdown => Some(markdown::segments(text)),
+            InputFormat::Html => Some(html::segments(text)),
+            InputFormat::Ansi => Some(ansi::segments(text)),
+        };
+        // For defanged links, scan in the text with the conventions replaced
+        let segments = if finder.defanged {
+            let segments = segments.unwrap_or_else(|| vec![Segment::Text(0..text.len())]);
+            let refanged = segments
+                .into_iter()
+                .map(|segment| defang::refang_segment(text, segment))
+                .collect();
+            Some(refanged)
+        } else {
+            segments
+        };
+        let (segment, segments) = match segments {
+            None => (Some(Segment::Text(0..text.len())), Vec::new()),
+            Some(mut segments) => {
+                segments.retain(|segment| match segment {
+                    Segment::Text(_) | Segment::Mapped { .. } => true,
+                    Segment::Existing { kind, .. } => {
+                        finder.existing_links
+                            && match kind {
+                                LinkKind::Url => finder.url,
+                                LinkKind::Email => finder.email,
+                                LinkKind::Phone => finder.phone,
+                                LinkKind::FediverseHandle => finder.fediverse,
+                                LinkKind::Mention => finder.mention,
+                                LinkKind::Hashtag => finder.hashtag,
+                                LinkKind::Reference => finder.reference,
+                            }
+                    }
+                });
+                (None, segments)
+            }
+        };
+
         let url_scanner = UrlScanner {
-            iri_parsing_enabled,
+            iri_parsing_enabled: finder.url_can_be_iri,
+            scheme_policy: finder.scheme_policy.clone(),
         };
         let domain_scanner = DomainScanner {
-            iri_parsing_enabled,
+            iri_parsing_enabled: finder.url_can_be_iri,
+            tld_validator: finder.tld_validator.clone(),
         };
-        let email_scanner = EmailScanner {
-            domain_must_have_dot: email_domain_must_have_dot,
+        let email_scanner = if finder.email {
+            Some(EmailScanner {
+                domain_must_have_dot: finder.email_domain_must_have_dot,
+                tld_validator: finder.tld_validator.clone(),
+            })
+        } else {
+            None
+        };
+        let fediverse_scanner = if finder.fediverse {
+            Some(FediverseScanner {
+                iri_parsing_enabled: finder.url_can_be_iri,
+                tld_validator: finder.tld_validator.clone(),
+            })
+        } else {
+            None
+        };
+        let mention_scanner = if finder.mention {
+            Some(TagScanner {
+                rules: finder.mention_rules.clone(),
+            })
+        } else {
+            None
+        };
+        let hashtag_scanner = if finder.hashtag {
+            Some(TagScanner {
+                rules: finder.hashtag_rules.clone(),
+            })
+        } else {
+            None
         };
 
-        // With optional schemes URLs don't have unique `:`, then search for `.` as well
-        let trigger_finder: Box<TriggerFinder> = match (url, email) {
-            (true, true) if url_must_have_scheme => Box::new(|s| memchr2(b':', b'@', s)),
-            (true, true) => Box::new(|s| memchr3(b':', b'@', b'.', s)),
-            (true, false) if url_must_have_scheme => Box::new(|s| memchr(b':', s)),
-            (true, false) => Box::new(|s| memchr2(b':', b'.', s)),
-            (false, true) => Box::new(|s| memchr(b'@', s)),
-            (false, false) => Box::new(|_| None),
+        let phone_scanner = if finder.phone {
+            Some(PhoneScanner)
+        } else {
+            None
         };
-        Links {
+        let reference_scanner = if finder.reference {
+            Some(ReferenceScanner {
+                rules: finder.reference_rules.clone(),
+            })
+        } else {
+            None
+        };
+
+        let mut triggers = Vec::new();
+        if finder.url {
+            triggers.push(b':');
+            // With optional schemes URLs don't have unique `:`, then search for `.` as well
+            if !finder.url_must_have_scheme {
+                triggers.push(b'.');
+            }
+        }
+        if finder.email || finder.fediverse || finder.mention {
+            triggers.push(b'@');
+        }
+        if finder.hashtag || finder.reference {
+            triggers.push(b'#');
+        }
+        // Commits and tickets are found starting from a digit too
+        if finder.phone || finder.reference {
+            triggers.extend(b'0'..=b'9');
+        }
+        let trigger_finder: Box<TriggerFinder> = match triggers[..] {
+            [] => Box::new(|_| None),
+            [a] => Box::new(move |s| memchr(a, s)),
+            [a, b] => Box::new(move |s| memchr2(a, b, s)),
+            [a, b, c] => Box::new(move |s| memchr3(a, b, c, s)),
+            _ => {
+                // Too many for memchr (e.g. digits for phone numbers), use a lookup table
+                let mut table = [false; 256];
+                for trigger in triggers {
+                    table[trigger as usize] = true;
+                }
+                Box::new(move |s| s.iter().position(|&b| table[b as usize]))
+            }
+        };
+        let mut links = Links {
             text,
+            offset: 0,
             rewind: 0,
+            after_link: false,
+            segment,
+            segments: segments.into_iter(),
+            trim_escapes: finder.input_format == InputFormat::Ansi,
+            refang: finder.defanged,
             trigger_finder,
             email_scanner,
             url_scanner,
             domain_scanner,
+            fediverse_scanner,
+            mention_scanner,
+            hashtag_scanner,
+            phone_scanner,
+            reference_scanner,
+        };
+        if links.segment.is_none() {
+            links.next_segment();
         }
+        links
     }
-}
 
-impl<'t> Iterator for Links<'t> {
-    type Item = Link<'t>;
+    fn next_segment(&mut self) {
+        self.segment = self.segments.next();
+        self.after_link = false;
+        match &self.segment {
+            Some(Segment::Text(range)) => self.rewind = range.start,
+            Some(Segment::Mapped { .. }) => self.rewind = 0,
+            _ => {}
+        }
+    }
 
-    fn next(&mut self) -> Option<Link<'t>> {
-        let slice = &self.text[self.rewind..];
+    fn link(
+        &self,
+        range: Range<usize>,
+        kind: LinkKind,
+        has_scheme: bool,
+        existing: bool,
+    ) -> Link<'t> {
+        Link {
+            text: self.text,
+            start: self.offset + range.start,
+            end: self.offset + range.end,
+            kind,
+            has_scheme,
+            existing,
+            defanged: false,
+            offset: self.offset,
+            reference_rules: match kind {
+                LinkKind::Reference => self
+                    .reference_scanner
+                    .as_ref()
+                    .map(|scanner| scanner.rules.clone()),
+                _ => None,
+            },
+        }
+    }
 
+    /// Scan for the next link in the slice, returning its range, kind and whether it has a scheme.
+    fn find_link(&self, slice: &str) -> Option<(Range<usize>, LinkKind, bool)> {
         let mut find_from = 0;
         while let Some(i) = (self.trigger_finder)(&slice.as_bytes()[find_from..]) {
             let trigger = slice.as_bytes()[find_from + i];
-            let (scanner, kind): (&dyn Scanner, LinkKind) = match trigger {
-                b':' => (&self.url_scanner, LinkKind::Url),
-                b'.' => (&self.domain_scanner, LinkKind::Url),
-                b'@' => (&self.email_scanner, LinkKind::Email),
+            // A sigil right after the previous link (e.g. `#frag` in `http://a.com#frag`) can't
+            // start a tag, but the scanners can't see what's before the slice
+            let after_link = self.after_link && find_from + i == 0;
+            let scan = |scanner: &dyn Scanner, kind: LinkKind| {
+                scanner
+                    .scan(slice, find_from + i)
+                    .map(|range| (range, kind))
+            };
+            let found = match trigger {
+                b':' => scan(&self.url_scanner, LinkKind::Url),
+                b'.' => scan(&self.domain_scanner, LinkKind::Url),
+                b'@' => self
+                    .email_scanner
+                    .as_ref()
+                    .and_then(|scanner| scan(scanner, LinkKind::Email))
+                    .or_else(|| {
+                        self.fediverse_scanner
+                            .as_ref()
+                            .and_then(|scanner| scan(scanner, LinkKind::FediverseHandle))
+                    })
+                    .or_else(|| {
+                        self.mention_scanner
+                            .as_ref()
+                            .filter(|_| !after_link)
+                            .and_then(|scanner| scan(scanner, LinkKind::Mention))
+                    }),
+                b'#' if after_link => None,
+                b'#' => self
+                    .reference_scanner
+                    .as_ref()
+                    .and_then(|scanner| scan(scanner, LinkKind::Reference))
+                    .or_else(|| {
+                        self.hashtag_scanner
+                            .as_ref()
+                            .and_then(|scanner| scan(scanner, LinkKind::Hashtag))
+                    }),
+                b'0'..=b'9' => self
+                    .phone_scanner
+                    .as_ref()
+                    .and_then(|scanner| scan(scanner, LinkKind::Phone))
+                    .or_else(|| {
+                        self.reference_scanner
+                            .as_ref()
+                            .and_then(|scanner| scan(scanner, LinkKind::Reference))
+                    }),
                 _ => unreachable!(),
             };
-            if let Some(range) = scanner.scan(slice, find_from + i) {
-                let start = self.rewind + range.start;
-                let end = self.rewind + range.end;
-                self.rewind = end;
-                let link = Link {
-                    text: self.text,
-                    start,
-                    end,
-                    kind,
-                };
-                return Some(link);
+            if let Some((range, kind)) = found {
+                return Some((range, kind, trigger == b':'));
             } else {
                 // The scanner didn't find anything. But there could be more
                 // trigger characters later, so continue the search.
@@ -290,12 +1429,117 @@ impl<'t> Iterator for Links<'t> {
     }
 }
 
+impl<'t> Iterator for Links<'t> {
+    type Item = Link<'t>;
+
+    fn next(&mut self) -> Option<Link<'t>> {
+        loop {
+            match self.segment.as_ref()? {
+                Segment::Text(range) => {
+                    let slice = &self.text[self.rewind..range.end];
+                    if let Some((found, kind, has_scheme)) = self.find_link(slice) {
+                        let start = self.rewind + found.start;
+                        let end = self.rewind + found.end;
+                        self.rewind = end;
+                        self.after_link = true;
+                        return Some(self.link(start..end, kind, has_scheme, false));
+                    }
+                }
+                Segment::Mapped { text, offsets } => {
+                    if let Some((found, kind, has_scheme)) = self.find_link(&text[self.rewind..]) {
+                        let start = offsets[self.rewind + found.start];
+                        let mut end = offsets[self.rewind + found.end];
+                        if self.trim_escapes {
+                            end = ansi::trim_end(self.text, start, end);
+                        }
+                        self.rewind += found.end;
+                        self.after_link = true;
+                        let mut link = self.link(start..end, kind, has_scheme, false);
+                        // Text with defang conventions is mapped, see `defang::refang_segment`
+                        link.defanged = self.refang
+                            && defang::is_defanged(link.as_str(), &link.kind, has_scheme);
+                        return Some(link);
+                    }
+                }
+                Segment::Existing {
+                    range,
+                    kind,
+                    has_scheme,
+                } => {
+                    let link = self.link(range.clone(), *kind, *has_scheme, true);
+                    self.next_segment();
+                    return Some(link);
+                }
+            }
+            self.next_segment();
+        }
+    }
+}
+
 impl<'t> fmt::Debug for Links<'t> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Links").field("text", &self.text).finish()
     }
 }
 
+/// Iterator for finding links in bytes, see [`LinkFinder::links_bytes`].
+pub struct ByteLinks<'t> {
+    bytes: &'t [u8],
+    position: usize,
+    finder: LinkFinder,
+    links: Option<Links<'t>>,
+}
+
+impl<'t> ByteLinks<'t> {
+    /// The next valid UTF-8 part of the input and its index.
+    fn next_valid(&mut self) -> Option<(usize, &'t str)> {
+        while self.position < self.bytes.len() {
+            let start = self.position;
+            let rest = &self.bytes[start..];
+            match str::from_utf8(rest) {
+                Ok(valid) => {
+                    self.position = self.bytes.len();
+                    return Some((start, valid));
+                }
+                Err(e) => {
+                    let valid_len = e.valid_up_to();
+                    let invalid_len = e.error_len().unwrap_or(rest.len() - valid_len);
+                    self.position += valid_len + invalid_len;
+                    if valid_len > 0 {
+                        let valid = str::from_utf8(&rest[..valid_len]).unwrap();
+                        return Some((start, valid));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<'t> Iterator for ByteLinks<'t> {
+    type Item = Link<'t>;
+
+    fn next(&mut self) -> Option<Link<'t>> {
+        loop {
+            if let Some(link) = self.links.as_mut().and_then(|links| links.next()) {
+                return Some(link);
+            }
+            let (offset, text) = self.next_valid()?;
+            let mut links = Links::new(text, &self.finder);
+            links.offset = offset;
+            self.links = Some(links);
+        }
+    }
+}
+
+impl<'t> fmt::Debug for ByteLinks<'t> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ByteLinks")
+            .field("bytes", &self.bytes)
+            .finish()
+    }
+}
+
 impl<'t> Iterator for Spans<'t> {
     type Item = Span<'t>;
 
@@ -308,6 +1552,10 @@ impl<'t> Iterator for Spans<'t> {
                         start: self.position,
                         end: link.start,
                         kind: None,
+                        has_scheme: false,
+                        existing: false,
+                        defanged: false,
+                        reference_rules: None,
                     };
                     self.position = link.start;
                     return Some(span);
@@ -320,6 +1568,10 @@ impl<'t> Iterator for Spans<'t> {
                         start: self.position,
                         end: self.text.len(),
                         kind: None,
+                        has_scheme: false,
+                        existing: false,
+                        defanged: false,
+                        reference_rules: None,
                     };
                     self.position = self.text.len();
                     return Some(span);
@@ -333,6 +1585,10 @@ impl<'t> Iterator for Spans<'t> {
                 start: link.start,
                 end: link.end,
                 kind: Some(link.kind),
+                has_scheme: link.has_scheme,
+                existing: link.existing,
+                defanged: link.defanged,
+                reference_rules: link.reference_rules,
             }
         })
     }
@@ -0,0 +1,195 @@
+use std::ops::Range;
+
+use unicode_security::{
+    skeleton, GeneralSecurityProfile, RestrictionLevel, RestrictionLevelDetection,
+};
+
+use crate::iri::is_invisible;
+
+/// Analysis of a host for homograph spoofing, e.g. `аpple.com` with a Cyrillic `а` that looks like
+/// `apple.com`. See [`Link::host_analysis`](crate::Link::host_analysis).
+///
+/// The checks use the data of Unicode TR39 (via the `unicode-security` crate). Labels in Punycode
+/// (`xn--`) are decoded first, so `xn--pple-43d.com` is analyzed like `аpple.com`.
+///
+/// Positions are byte indices within the input text, like with
+/// [`Components`](crate::Components). Characters of a decoded label have the position of the
+/// start of the label.
+///
+/// ```
+/// use linkify::LinkFinder;
+///
+/// let finder = LinkFinder::new();
+/// let spoof = finder.links("https://\u{430}pple.com").next().unwrap();
+/// let analysis = spoof.host_analysis().unwrap();
+///
+/// assert!(analysis.is_suspicious());
+/// assert_eq!(&[(8, '\u{430}')], analysis.confusables());
+/// assert_eq!("xn--pple-43d.com", analysis.punycode());
+///
+/// let real = finder.links("https://apple.com").next().unwrap();
+/// assert_eq!(real.host_analysis().unwrap().skeleton(), analysis.skeleton());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HostAnalysis {
+    mixed_script_labels: Vec<Range<usize>>,
+    confusables: Vec<(usize, char)>,
+    invisible_chars: Vec<(usize, char)>,
+    lookalike_labels: Vec<Range<usize>>,
+    skeleton: String,
+    punycode: String,
+}
+
+impl HostAnalysis {
+    /// Analyze the host, with `position` mapping each byte index of it (and its length) to the
+    /// index in the input text.
+    pub(crate) fn analyze<F: Fn(usize) -> usize>(host: &str, position: F) -> HostAnalysis {
+        let mut analysis = HostAnalysis {
+            mixed_script_labels: Vec::new(),
+            confusables: Vec::new(),
+            invisible_chars: Vec::new(),
+            lookalike_labels: Vec::new(),
+            skeleton: String::with_capacity(host.len()),
+            punycode: String::new(),
+        };
+
+        let mut label_start = 0;
+        for (i, label) in host.split('.').enumerate() {
+            if i > 0 {
+                analysis.skeleton.push('.');
+            }
+            let range = position(label_start)..position(label_start + label.len());
+
+            let decoded = decode(label);
+            let chars: Vec<(usize, char)> = match &decoded {
+                Some(decoded) => decoded.chars().map(|c| (range.start, c)).collect(),
+                None => label
+                    .char_indices()
+                    .map(|(j, c)| (position(label_start + j), c))
+                    .collect(),
+            };
+
+            let mut lowercase = String::with_capacity(label.len());
+            for (pos, c) in chars {
+                if is_invisible(c) {
+                    analysis.invisible_chars.push((pos, c));
+                    continue;
+                }
+                let start = lowercase.len();
+                lowercase.extend(c.to_lowercase());
+                if !c.is_ascii() && skeleton(&lowercase[start..]).all(|s| s.is_ascii()) {
+                    analysis.confusables.push((pos, c));
+                }
+            }
+
+            if is_mixed_script(&lowercase) {
+                analysis.mixed_script_labels.push(range.clone());
+            }
+            let start = analysis.skeleton.len();
+            analysis.skeleton.extend(skeleton(&lowercase));
+            if !lowercase.is_ascii() && analysis.skeleton[start..].is_ascii() {
+                analysis.lookalike_labels.push(range);
+            }
+            label_start += label.len() + 1;
+        }
+        analysis.punycode = to_ascii(host);
+        analysis
+    }
+
+    /// Whether the host looks like it could be used for spoofing: if it has labels with mixed
+    /// scripts, invisible characters, or labels that only consist of characters that look like
+    /// ASCII (e.g. `аррӏе` in Cyrillic).
+    ///
+    /// Confusable characters alone are not suspicious, e.g. `пример.com` contains `р` which looks
+    /// like `p`, but the label is clearly not a lookalike of an ASCII label.
+    pub fn is_suspicious(&self) -> bool {
+        !self.mixed_script_labels.is_empty()
+            || !self.invisible_chars.is_empty()
+            || !self.lookalike_labels.is_empty()
+    }
+
+    /// The labels that mix characters of different scripts, e.g. Latin and Cyrillic in `аpple`.
+    ///
+    /// These are the labels that don't meet the "Highly Restrictive" level of Unicode TR39, so
+    /// combinations that are common in a language (e.g. Latin, Han and Hiragana for Japanese) are
+    /// not counted as mixed.
+    pub fn mixed_script_labels(&self) -> &[Range<usize>] {
+        &self.mixed_script_labels
+    }
+
+    /// The non-ASCII characters that are confusable with ASCII characters according to Unicode
+    /// TR39, with their position, e.g. the Cyrillic `а` or the Greek `ο`.
+    pub fn confusables(&self) -> &[(usize, char)] {
+        &self.confusables
+    }
+
+    /// Invisible characters such as zero width spaces, soft hyphens or bidirectional controls,
+    /// with their position.
+    pub fn invisible_chars(&self) -> &[(usize, char)] {
+        &self.invisible_chars
+    }
+
+    /// The non-ASCII labels that look like ASCII labels, e.g. `аррӏе` in Cyrillic.
+    pub fn lookalike_labels(&self) -> &[Range<usize>] {
+        &self.lookalike_labels
+    }
+
+    /// The skeleton of the host as defined by Unicode TR39, computed for each label after
+    /// lowercasing it and removing invisible characters. Two hosts with the same skeleton are
+    /// likely to be confused.
+    ///
+    /// The skeleton is meant for comparing hosts, not for display: ASCII characters are mapped too,
+    /// e.g. `apple.com` has the skeleton `apple.corn`.
+    pub fn skeleton(&self) -> &str {
+        &self.skeleton
+    }
+
+    /// The host in a form that is safe to display, with non-ASCII labels encoded as Punycode,
+    /// e.g. `xn--pple-43d.com` for `аpple.com`.
+    ///
+    /// The host is converted using IDNA, which also maps characters, e.g. full-width forms to
+    /// ASCII. If the host is not valid according to IDNA (e.g. because of invisible characters),
+    /// labels are only lowercased and encoded.
+    pub fn punycode(&self) -> &str {
+        &self.punycode
+    }
+}
+
+/// Decode a label in Punycode (with an `xn--` prefix), or `None` if it's not one.
+fn decode(label: &str) -> Option<String> {
+    let prefix = label.get(..4)?;
+    if prefix.eq_ignore_ascii_case("xn--") {
+        idna::punycode::decode_to_string(&label[4..])
+    } else {
+        None
+    }
+}
+
+fn is_mixed_script(label: &str) -> bool {
+    // Characters that are not allowed in identifiers (e.g. full-width forms) would make the level
+    // "Unrestricted", they are checked with their skeleton instead.
+    let allowed: String = label.chars().filter(|c| c.identifier_allowed()).collect();
+    allowed.detect_restriction_level() > RestrictionLevel::HighlyRestrictive
+}
+
+/// The host with non-ASCII labels encoded as Punycode, see [`HostAnalysis::punycode`].
+fn to_ascii(host: &str) -> String {
+    if let Ok(ascii) = idna::domain_to_ascii(host) {
+        return ascii;
+    }
+    let labels: Vec<String> = host
+        .split('.')
+        .map(|label| {
+            if label.is_ascii() {
+                label.to_ascii_lowercase()
+            } else {
+                let lowercase: String = label.chars().flat_map(char::to_lowercase).collect();
+                match idna::punycode::encode_str(&lowercase) {
+                    Some(encoded) => format!("xn--{}", encoded),
+                    None => lowercase,
+                }
+            }
+        })
+        .collect();
+    labels.join(".")
+}
@@ -0,0 +1,567 @@
+//! Rendering of text with links as HTML.
+//!
+//! For finding links in HTML input (as opposed to rendering plain text as HTML), see
+//! [`InputFormat::Html`](crate::InputFormat::Html).
+//!
+//! The [`HtmlRenderer`] turns the spans of a text into HTML, with links wrapped in `<a>` elements
+//! and all text escaped. URLs with schemes that can run scripts (such as `javascript:`) are not
+//! linked by default:
+//!
+//! ```
+//! use linkify::html::HtmlRenderer;
+//! use linkify::{LinkFinder, LinkKind};
+//!
+//! let mut finder = LinkFinder::new();
+//! finder.url_must_have_scheme(false);
+//!
+//! let mut renderer = HtmlRenderer::new();
+//! renderer.rel("nofollow").class(LinkKind::Email, "email");
+//!
+//! let html = renderer.render(finder.spans("Go to example.org or mail <foo@example.org>"));
+//! assert_eq!(
+//!     html,
+//!     "Go to <a href=\"https://example.org\" rel=\"nofollow\">example.org</a> or mail \
+//!      &lt;<a href=\"mailto:foo@example.org\" rel=\"nofollow\" class=\"email\">foo@example.org</a>&gt;"
+//! );
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Range;
+
+use memchr::memchr;
+
+use crate::finder::{Link, LinkKind, Segment, Spans};
+use crate::phone;
+
+/// What to do with a link when rendering, see [`HtmlRenderer::filter`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum LinkAction {
+    /// Render the link as usual.
+    Link,
+    /// Don't render a link, only the escaped text.
+    Text,
+    /// Render the link with the specified `href` instead of the default one. The value is escaped
+    /// when rendering.
+    Href(String),
+}
+
+type Filter = dyn Fn(&Link<'_>) -> LinkAction + Send + Sync;
+
+/// Renders spans as HTML with links, see the [module docs](self).
+pub struct HtmlRenderer {
+    default_scheme: String,
+    attributes: Vec<(Option<LinkKind>, String, String)>,
+    filter: Option<Box<Filter>>,
+}
+
+impl HtmlRenderer {
+    /// Create a new renderer that renders links without any attributes except `href`.
+    pub fn new() -> HtmlRenderer {
+        HtmlRenderer {
+            default_scheme: "https".to_string(),
+            attributes: Vec::new(),
+            filter: None,
+        }
+    }
+
+    /// Set the scheme to use in the `href` of URLs without a scheme such as `example.org`,
+    /// defaults to `https`.
+    pub fn default_scheme(&mut self, scheme: &str) -> &mut HtmlRenderer {
+        self.default_scheme = scheme.to_string();
+        self
+    }
+
+    /// Set the `rel` attribute for all links, e.g. `nofollow noopener`.
+    pub fn rel(&mut self, rel: &str) -> &mut HtmlRenderer {
+        self.attribute(None, "rel", rel)
+    }
+
+    /// Set the `target` attribute for all links, e.g. `_blank`.
+    pub fn target(&mut self, target: &str) -> &mut HtmlRenderer {
+        self.attribute(None, "target", target)
+    }
+
+    /// Set the `class` attribute for links of the specified kind.
+    pub fn class(&mut self, kind: LinkKind, class: &str) -> &mut HtmlRenderer {
+        self.attribute(Some(kind), "class", class)
+    }
+
+    /// Add an attribute for links of the specified kind, or all links if the kind is `None`.
+    ///
+    /// Attributes are rendered in the order they were added. Setting an attribute again for the
+    /// same kind replaces the previous value. The value is escaped when rendering, but the name
+    /// is not.
+    pub fn attribute(
+        &mut self,
+        kind: Option<LinkKind>,
+        name: &str,
+        value: &str,
+    ) -> &mut HtmlRenderer {
+        match self
+            .attributes
+            .iter_mut()
+            .find(|(k, n, _)| *k == kind && n == name)
+        {
+            Some(attribute) => attribute.2 = value.to_string(),
+            None => self
+                .attributes
+                .push((kind, name.to_string(), value.to_string())),
+        }
+        self
+    }
+
+    /// Set a function that decides for each link whether it should be rendered as a link, as
+    /// plain text, or with a different `href`.
+    ///
+    /// Mentions and hashtags are rendered as plain text unless the filter returns
+    /// `LinkAction::Href` for them. The same goes for references without a URL template (see
+    /// [`Link::reference_url`]) and for URLs with a scheme that can run scripts in a browser
+    /// (`javascript:`, `vbscript:` and `data:`).
+    ///
+    /// ```
+    /// use linkify::html::{HtmlRenderer, LinkAction};
+    /// use linkify::LinkFinder;
+    ///
+    /// let mut renderer = HtmlRenderer::new();
+    /// renderer.filter(|link| {
+    ///     if link.as_str().starts_with("http:") {
+    ///         LinkAction::Text
+    ///     } else {
+    ///         LinkAction::Link
+    ///     }
+    /// });
+    ///
+    /// let finder = LinkFinder::new();
+    /// let html = renderer.render(finder.spans("http://example.org https://example.org"));
+    /// assert_eq!(
+    ///     html,
+    ///     "http://example.org <a href=\"https://example.org\">https://example.org</a>"
+    /// );
+    /// ```
+    pub fn filter<F>(&mut self, filter: F) -> &mut HtmlRenderer
+    where
+        F: Fn(&Link<'_>) -> LinkAction + Send + Sync + 'static,
+    {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Render the spans as HTML.
+    pub fn render(&self, spans: Spans<'_>) -> String {
+        let mut html = String::new();
+        self.render_to(spans, &mut html)
+            .expect("writing to a String doesn't fail");
+        html
+    }
+
+    /// Render the spans as HTML to the specified writer.
+    pub fn render_to<W: fmt::Write>(&self, spans: Spans<'_>, out: &mut W) -> fmt::Result {
+        for span in spans {
+            match span.link() {
+                Some(link) => self.render_link(&link, out)?,
+                None => escape(span.as_str(), out)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn render_link<W: fmt::Write>(&self, link: &Link<'_>, out: &mut W) -> fmt::Result {
+        let action = match &self.filter {
+            Some(filter) => filter(link),
+            None => LinkAction::Link,
+        };
+        let href = match action {
+            // Mentions and hashtags don't have a URL unless the filter provides one
+            LinkAction::Link if matches!(link.kind(), LinkKind::Mention | LinkKind::Hashtag) => {
+                return escape(link.as_str(), out)
+            }
+            LinkAction::Link if *link.kind() == LinkKind::Reference => match link.reference_url() {
+                Some(url) => url,
+                None => return escape(link.as_str(), out),
+            },
+            LinkAction::Link => {
+                let href = href(link, &self.default_scheme);
+                if is_unsafe_href(&href) {
+                    return escape(link.as_str(), out);
+                }
+                href
+            }
+            LinkAction::Text => return escape(link.as_str(), out),
+            LinkAction::Href(href) => href,
+        };
+
+        out.write_str("<a href=\"")?;
+        escape(&href, out)?;
+        out.write_str("\"")?;
+        for (kind, name, value) in &self.attributes {
+            if kind.is_none() || kind.as_ref() == Some(link.kind()) {
+                write!(out, " {}=\"", name)?;
+                escape(value, out)?;
+                out.write_str("\"")?;
+            }
+        }
+        out.write_str(">")?;
+        escape(link.as_str(), out)?;
+        out.write_str("</a>")
+    }
+}
+
+impl Default for HtmlRenderer {
+    fn default() -> Self {
+        HtmlRenderer::new()
+    }
+}
+
+impl fmt::Debug for HtmlRenderer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HtmlRenderer")
+            .field("default_scheme", &self.default_scheme)
+            .field("attributes", &self.attributes)
+            .finish()
+    }
+}
+
+/// The URL to link to for the specified link (not escaped).
+///
+/// URLs without a scheme get the specified default scheme (e.g. `https://example.org` for
+/// `example.org`), emails get `mailto:` and phone numbers get `tel:` with the number normalized
+/// (see [`Link::phone_number`]). Fediverse handles link to the profile on their instance, e.g.
+/// `https://mastodon.social/@alice` for `@alice@mastodon.social`, and references get the URL from
+/// the templates (see [`Link::reference_url`]). Other links (e.g. mentions) are returned unchanged.
+pub fn href(link: &Link<'_>, default_scheme: &str) -> String {
+    match link.kind() {
+        LinkKind::Url if !link.has_scheme() => {
+            format!("{}://{}", default_scheme, link.as_str())
+        }
+        LinkKind::Email => format!("mailto:{}", link.as_str()),
+        LinkKind::Phone => format!("tel:{}", phone::normalize(link.as_str())),
+        LinkKind::Reference => link
+            .reference_url()
+            .unwrap_or_else(|| link.as_str().to_string()),
+        LinkKind::FediverseHandle => {
+            let handle = link.as_str();
+            match handle[1..].find('@') {
+                Some(at) => format!("https://{}/{}", &handle[at + 2..], &handle[..at + 1]),
+                None => handle.to_string(),
+            }
+        }
+        _ => link.as_str().to_string(),
+    }
+}
+
+/// Whether the `href` has a scheme that can run scripts when the link is clicked.
+fn is_unsafe_href(href: &str) -> bool {
+    match href.find(':') {
+        Some(colon) => {
+            let scheme = &href[..colon];
+            ["javascript", "vbscript", "data"]
+                .iter()
+                .any(|unsafe_scheme| scheme.eq_ignore_ascii_case(unsafe_scheme))
+        }
+        None => false,
+    }
+}
+
+/// Escape text for use in HTML content or attribute values.
+pub fn escape<W: fmt::Write>(text: &str, out: &mut W) -> fmt::Result {
+    let mut last = 0;
+    for (i, b) in text.bytes().enumerate() {
+        let replacement = match b {
+            b'&' => "&amp;",
+            b'<' => "&lt;",
+            b'>' => "&gt;",
+            b'"' => "&quot;",
+            b'\'' => "&#39;",
+            _ => continue,
+        };
+        out.write_str(&text[last..i])?;
+        out.write_str(replacement)?;
+        last = i + 1;
+    }
+    out.write_str(&text[last..])
+}
+
+/// Split HTML text into segments, in order. Only text content outside of skipped elements is
+/// scanned, and `href` attributes of `<a>` elements are existing links.
+pub(crate) fn segments(text: &str) -> Vec<Segment> {
+    let bytes = text.as_bytes();
+    let mut segments = Vec::new();
+    // Depth of elements with contents that are skipped (`a`, `code` and `pre`)
+    let mut skipped = 0usize;
+    let mut unterminated = HashSet::new();
+    let mut text_start = 0;
+    let mut i = 0;
+    while let Some(lt) = memchr(b'<', &bytes[i..]) {
+        let tag_start = i + lt;
+        let tag = match parse_tag(text, tag_start, &mut unterminated) {
+            Some(tag) => tag,
+            None => {
+                // Not a tag, just text
+                i = tag_start + 1;
+                continue;
+            }
+        };
+        if skipped == 0 {
+            push_text(text, text_start..tag_start, &mut segments);
+        }
+
+        if let Some(name) = &tag.name {
+            let skip_contents = name == "a" || name == "code" || name == "pre";
+            if tag.closing {
+                if skip_contents {
+                    skipped = skipped.saturating_sub(1);
+                }
+            } else {
+                if let Some(href) = tag.href.filter(|href| href.start < href.end) {
+                    if name == "a" {
+                        segments.push(Segment::existing(text, href));
+                    }
+                }
+                if skip_contents && !tag.self_closing {
+                    skipped += 1;
+                }
+            }
+        }
+
+        text_start = tag.end;
+        i = tag.end;
+    }
+    if skipped == 0 {
+        push_text(text, text_start..text.len(), &mut segments);
+    }
+    segments
+}
+
+struct Tag {
+    /// End of the tag, or for `<script>` and `<style>` the end of the element
+    end: usize,
+    /// Lowercase name, `None` for comments, doctypes, etc
+    name: Option<String>,
+    closing: bool,
+    self_closing: bool,
+    href: Option<Range<usize>>,
+}
+
+/// Parse the tag starting at `<`. `unterminated` are the positions within tags from which no end
+/// of the tag was found before, see `parse_attributes`.
+fn parse_tag(text: &str, start: usize, unterminated: &mut HashSet<usize>) -> Option<Tag> {
+    let bytes = text.as_bytes();
+    let rest = &text[start..];
+    if let Some(comment) = rest.strip_prefix("<!--") {
+        let end = comment
+            .find("-->")
+            .map_or(text.len(), |i| start + 4 + i + 3);
+        return Some(Tag::other(end));
+    }
+    if rest.starts_with("<!") || rest.starts_with("<?") {
+        let end = memchr(b'>', &bytes[start..]).map_or(text.len(), |i| start + i + 1);
+        return Some(Tag::other(end));
+    }
+
+    let closing = bytes.get(start + 1) == Some(&b'/');
+    let name_start = if closing { start + 2 } else { start + 1 };
+    if !bytes.get(name_start)?.is_ascii_alphabetic() {
+        return None;
+    }
+    let name_end = name_start
+        + bytes[name_start..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric() || **b == b'-' || **b == b':')
+            .count();
+    let name = text[name_start..name_end].to_ascii_lowercase();
+
+    let mut visited = Vec::new();
+    let attributes = parse_attributes(text, name_end, unterminated, &mut visited);
+    if attributes.is_none() {
+        unterminated.extend(visited);
+    }
+    let (mut end, href, self_closing) = attributes?;
+
+    if !closing && (name == "script" || name == "style") {
+        // Raw text, skip until the closing tag
+        let closing_tag = format!("</{}", name);
+        end = match find_ignore_case(bytes, end, closing_tag.as_bytes()) {
+            Some(closing_start) => {
+                memchr(b'>', &bytes[closing_start..]).map_or(text.len(), |k| closing_start + k + 1)
+            }
+            None => text.len(),
+        };
+    }
+
+    Some(Tag {
+        end,
+        name: Some(name),
+        closing,
+        self_closing,
+        href,
+    })
+}
+
+/// Parse the attributes of a tag after the name, returning the end of the tag, the range of the
+/// `href` value and whether the tag is self-closing.
+///
+/// Where the tag continues from a position doesn't depend on where it started, so if no end is
+/// found, the positions that were visited are remembered in `unterminated`. Other tags that get
+/// to one of them stop there, instead of scanning the rest of the text again for each `<`.
+fn parse_attributes(
+    text: &str,
+    name_end: usize,
+    unterminated: &HashSet<usize>,
+    visited: &mut Vec<usize>,
+) -> Option<(usize, Option<Range<usize>>, bool)> {
+    let bytes = text.as_bytes();
+    let mut href = None;
+    let mut self_closing = false;
+    let mut i = name_end;
+    loop {
+        // Directly after the name an attribute can't start, unlike at the same position in
+        // another tag
+        if i != name_end {
+            if unterminated.contains(&i) {
+                return None;
+            }
+            visited.push(i);
+        }
+        match *bytes.get(i)? {
+            b'>' => break,
+            b'/' => {
+                self_closing = bytes.get(i + 1) == Some(&b'>');
+                i += 1;
+            }
+            b if b.is_ascii_whitespace() => i += 1,
+            _ if i == name_end => return None,
+            _ => {
+                let attribute_start = i;
+                i += bytes[i..]
+                    .iter()
+                    .take_while(|&&b| !b.is_ascii_whitespace() && !matches!(b, b'=' | b'>' | b'/'))
+                    .count();
+                let attribute = &text[attribute_start..i];
+                i += whitespace(&bytes[i..]);
+                if bytes.get(i) != Some(&b'=') {
+                    continue;
+                }
+                i += 1;
+                i += whitespace(&bytes[i..]);
+                let value = match *bytes.get(i)? {
+                    quote @ b'"' | quote @ b'\'' => {
+                        let end = i + 1 + memchr(quote, &bytes[i + 1..])?;
+                        let value = i + 1..end;
+                        i = end + 1;
+                        value
+                    }
+                    _ => {
+                        let value_start = i;
+                        i += bytes[i..]
+                            .iter()
+                            .take_while(|&&b| !b.is_ascii_whitespace() && b != b'>')
+                            .count();
+                        value_start..i
+                    }
+                };
+                if attribute.eq_ignore_ascii_case("href") {
+                    href = Some(value);
+                }
+            }
+        }
+    }
+    Some((i + 1, href, self_closing))
+}
+
+/// Find the ASCII `needle` (starting with `<`) case-insensitively from the position.
+fn find_ignore_case(bytes: &[u8], mut i: usize, needle: &[u8]) -> Option<usize> {
+    while let Some(j) = memchr(needle[0], &bytes[i..]) {
+        let candidate = &bytes[i + j..];
+        if candidate.len() >= needle.len() && candidate[..needle.len()].eq_ignore_ascii_case(needle)
+        {
+            return Some(i + j);
+        }
+        i += j + 1;
+    }
+    None
+}
+
+impl Tag {
+    fn other(end: usize) -> Tag {
+        Tag {
+            end,
+            name: None,
+            closing: false,
+            self_closing: false,
+            href: None,
+        }
+    }
+}
+
+fn whitespace(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|b| b.is_ascii_whitespace()).count()
+}
+
+/// Add a segment for the text content, with entities decoded if it contains any.
+fn push_text(text: &str, range: Range<usize>, segments: &mut Vec<Segment>) {
+    let content = &text[range.clone()];
+    if content.is_empty() {
+        return;
+    }
+    if !content.contains('&') {
+        segments.push(Segment::Text(range));
+        return;
+    }
+
+    let mut decoded = String::with_capacity(content.len());
+    let mut offsets = Vec::with_capacity(content.len() + 1);
+    let mut i = 0;
+    while i < content.len() {
+        let (c, len) = match decode_entity(&content[i..]) {
+            Some(entity) => entity,
+            None => {
+                let c = content[i..].chars().next().unwrap();
+                (c, c.len_utf8())
+            }
+        };
+        decoded.push(c);
+        for _ in 0..c.len_utf8() {
+            offsets.push(range.start + i);
+        }
+        i += len;
+    }
+    offsets.push(range.end);
+    segments.push(Segment::Mapped {
+        text: decoded,
+        offsets,
+    });
+}
+
+/// Decode a character reference like `&amp;` or `&#38;` at the start of the text, returning the
+/// character and the length of the reference.
+fn decode_entity(s: &str) -> Option<(char, usize)> {
+    let bytes = s.as_bytes();
+    if bytes.first() != Some(&b'&') {
+        return None;
+    }
+    let semicolon = memchr(b';', &bytes[..bytes.len().min(12)])?;
+    let name = &s[1..semicolon];
+    let c = if let Some(number) = name.strip_prefix('#') {
+        let (digits, radix) = match number.strip_prefix(&['x', 'X'][..]) {
+            Some(hex) => (hex, 16),
+            None => (number, 10),
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return None;
+        }
+        std::char::from_u32(u32::from_str_radix(digits, radix).ok()?)?
+    } else {
+        match name {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            "nbsp" => '\u{a0}',
+            _ => return None,
+        }
+    };
+    Some((c, semicolon + 1))
+}
@@ -0,0 +1,142 @@
+use std::fmt::Write;
+use std::str;
+
+use crate::components::Components;
+use crate::finder::{Link, LinkKind};
+
+/// Convert a link to its ASCII form, with the host converted to Punycode using IDNA and other
+/// non-ASCII characters percent-encoded as UTF-8 (RFC 3987, section 3.1).
+pub(crate) fn to_ascii(link: &Link<'_>) -> Option<String> {
+    let text = link.as_str();
+    let components = Components::parse(text, 0, link.kind(), link.has_scheme());
+    let mut result = String::with_capacity(text.len());
+    match components.host() {
+        Some(host) if !text[host.clone()].is_ascii() => {
+            push_ascii(&text[..host.start], link.kind(), &mut result);
+            let ascii = idna::domain_to_ascii(&text[host.clone()]).ok()?;
+            result.push_str(&ascii);
+            push_ascii(&text[host.end..], link.kind(), &mut result);
+        }
+        _ => push_ascii(text, link.kind(), &mut result),
+    }
+    Some(result)
+}
+
+/// Percent-encode non-ASCII characters, except in the local part of emails which can be UTF-8.
+fn push_ascii(s: &str, kind: &LinkKind, result: &mut String) {
+    if *kind == LinkKind::Email {
+        result.push_str(s);
+        return;
+    }
+    for c in s.chars() {
+        if c.is_ascii() {
+            result.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for b in c.encode_utf8(&mut bytes).bytes() {
+                write!(result, "%{:02X}", b).expect("writing to a String doesn't fail");
+            }
+        }
+    }
+}
+
+/// Convert a link to a Unicode form for display, with Punycode labels of the host converted to
+/// Unicode and percent-encoded UTF-8 decoded (RFC 3987, section 3.2).
+pub(crate) fn to_unicode(link: &Link<'_>) -> String {
+    let text = link.as_str();
+    let components = Components::parse(text, 0, link.kind(), link.has_scheme());
+    let mut result = String::with_capacity(text.len());
+    match components.host() {
+        Some(host) if has_punycode_label(&text[host.clone()]) => {
+            push_decoded(&text[..host.start], &mut result);
+            let (unicode, errors) = idna::domain_to_unicode(&text[host.clone()]);
+            match errors {
+                Ok(()) => result.push_str(&unicode),
+                Err(_) => result.push_str(&text[host.clone()]),
+            }
+            push_decoded(&text[host.end..], &mut result);
+        }
+        _ => push_decoded(text, &mut result),
+    }
+    result
+}
+
+fn has_punycode_label(host: &str) -> bool {
+    host.split('.').any(|label| {
+        label.len() > 4 && label.is_char_boundary(4) && label[..4].eq_ignore_ascii_case("xn--")
+    })
+}
+
+/// Decode percent-encoded UTF-8 characters. ASCII characters, invalid UTF-8 and characters that
+/// are invisible (e.g. bidirectional controls) stay encoded.
+fn push_decoded(s: &str, result: &mut String) {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        // Collect a run of percent-encoded bytes
+        let mut decoded = Vec::new();
+        let mut j = i;
+        while j + 2 < bytes.len() && bytes[j] == b'%' {
+            match (hex_value(bytes[j + 1]), hex_value(bytes[j + 2])) {
+                (Some(high), Some(low)) => decoded.push(high << 4 | low),
+                _ => break,
+            }
+            j += 3;
+        }
+        if decoded.is_empty() {
+            let c = s[i..].chars().next().unwrap();
+            result.push(c);
+            i += c.len_utf8();
+            continue;
+        }
+
+        let mut rest = &decoded[..];
+        let mut encoded = &s[i..j];
+        while !rest.is_empty() {
+            let (valid, invalid) = match str::from_utf8(rest) {
+                Ok(valid) => (valid, 0),
+                Err(e) => {
+                    let valid = str::from_utf8(&rest[..e.valid_up_to()]).unwrap();
+                    (valid, e.error_len().unwrap_or(rest.len() - e.valid_up_to()))
+                }
+            };
+            for c in valid.chars() {
+                let len = c.len_utf8() * 3;
+                if c.is_ascii() || is_invisible(c) {
+                    result.push_str(&encoded[..len]);
+                } else {
+                    result.push(c);
+                }
+                encoded = &encoded[len..];
+            }
+            result.push_str(&encoded[..invalid * 3]);
+            encoded = &encoded[invalid * 3..];
+            rest = &rest[valid.len() + invalid..];
+        }
+        i = j;
+    }
+}
+
+/// Characters that are invisible or change the direction of text, such as zero width spaces, soft
+/// hyphens or bidirectional controls.
+pub(crate) fn is_invisible(c: char) -> bool {
+    matches!(c,
+        '\u{ad}'
+        | '\u{34f}'
+        | '\u{61c}'
+        | '\u{115f}'..='\u{1160}'
+        | '\u{17b4}'..='\u{17b5}'
+        | '\u{180e}'
+        | '\u{200b}'..='\u{200f}'
+        | '\u{202a}'..='\u{202e}'
+        | '\u{2060}'..='\u{2064}'
+        | '\u{2066}'..='\u{206f}'
+        | '\u{3164}'
+        | '\u{fe00}'..='\u{fe0f}'
+        | '\u{feff}'
+        | '\u{ffa0}')
+}
+
+fn hex_value(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
+}
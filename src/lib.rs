@@ -107,8 +107,9 @@
 //! At the same time, it does not guarantee that the returned links are valid.
 //! If in doubt, it rather returns a link than skipping it.
 //!
-//! If you need to validate URLs, e.g. for checking TLDs, use another library on
-//! the returned links.
+//! For checking top-level domains of URLs without a scheme and of emails, use
+//! `LinkFinder::tld_validator` (see also `is_known_tld` with the `tlds` feature).
+//! If you need to validate URLs further, use another library on the returned links.
 //!
 //! [RFC 3986]: https://datatracker.ietf.org/doc/html/rfc3986
 //! [RFC 3987]: https://datatracker.ietf.org/doc/html/rfc3987
@@ -119,17 +120,62 @@
 #![deny(missing_docs)]
 #![deny(missing_debug_implementations)]
 
+mod ansi;
+mod components;
+#[cfg(feature = "url")]
+mod convert;
+mod defang;
 mod domains;
 mod email;
+mod fediverse;
 mod finder;
+#[cfg(feature = "homograph")]
+mod homograph;
+pub mod html;
+#[cfg(feature = "idna")]
+mod iri;
+#[cfg(feature = "markdown")]
+mod markdown;
+mod opaque;
+mod options;
+mod phone;
+mod position;
+mod reference;
 mod scanner;
+mod scheme;
+#[cfg(feature = "serde")]
+mod serialization;
+mod stream;
+mod tag;
+pub mod terminal;
+#[cfg(feature = "tlds")]
+mod tlds;
 mod url;
 
+pub use crate::components::Components;
+#[cfg(feature = "url")]
+pub use crate::convert::ToUrlError;
+#[cfg(feature = "tlds")]
+pub use crate::domains::is_known_tld;
+pub use crate::domains::HostKind;
+pub use crate::finder::ByteLinks;
+pub use crate::finder::InputFormat;
 pub use crate::finder::Link;
 pub use crate::finder::LinkFinder;
 pub use crate::finder::LinkKind;
 pub use crate::finder::Links;
+pub use crate::finder::OwnedLink;
 pub use crate::finder::{Span, Spans};
+#[cfg(feature = "homograph")]
+pub use crate::homograph::HostAnalysis;
+pub use crate::options::LinkFinderOptions;
+pub use crate::position::{LineIndex, Position};
+pub use crate::reference::{Reference, ReferenceRules};
+pub use crate::scheme::{OpaqueSyntax, SchemePolicy, SchemeRules};
+#[cfg(feature = "async")]
+pub use crate::stream::LinkStream;
+pub use crate::stream::ReaderLinks;
+pub use crate::tag::TagRules;
 
 #[cfg(doctest)]
 doc_comment::doctest!("../README.md");
@@ -0,0 +1,517 @@
+//! Finding the parts of Markdown text that links should be searched in.
+//!
+//! This is not a full CommonMark parser, it only recognizes the constructs that links should not
+//! be found in:
+//!
+//! * Fenced and indented code blocks, and code spans
+//! * HTML comments and tags, including the contents of `<a>`, `<code>` and `<pre>` elements
+//! * Existing links: inline links and images like `[text](url)`, reference links like
+//!   `[text][label]` (if the label is defined), autolinks like `<url>` and link reference
+//!   definitions like `[label]: url`
+//!
+//! The destinations of existing links are returned as separate segments.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use memchr::memchr;
+
+use crate::finder::{scheme_length, Segment};
+
+/// Split the Markdown text into segments, in order.
+pub(crate) fn segments(text: &str) -> Vec<Segment> {
+    let (blocks, labels) = blocks(text);
+    let mut inlines = Inlines {
+        text,
+        labels: &labels,
+        segments: Vec::new(),
+        label_ends: HashMap::new(),
+        unclosed: HashMap::new(),
+    };
+    for block in blocks {
+        match block {
+            Block::Paragraph(range) => inlines.parse(range),
+            Block::Definition(destination) => {
+                inlines.segments.push(Segment::existing(text, destination));
+            }
+        }
+    }
+    inlines.segments
+}
+
+enum Block {
+    /// Text that can contain inlines (paragraphs, headings, list items, etc).
+    Paragraph(Range<usize>),
+    /// The destination of a link reference definition.
+    Definition(Range<usize>),
+}
+
+/// Find the blocks of the text. Code blocks are left out. Also returns the normalized labels of
+/// link reference definitions.
+fn blocks(text: &str) -> (Vec<Block>, HashSet<String>) {
+    let mut blocks = Vec::new();
+    let mut labels = HashSet::new();
+    let mut paragraph: Option<Range<usize>> = None;
+    let mut fence: Option<(u8, usize)> = None;
+    let mut in_indented_code = false;
+    let mut in_list = false;
+    let mut previous_blank = true;
+
+    let mut start = 0;
+    while start < text.len() {
+        let end = memchr(b'\n', &text.as_bytes()[start..]).map_or(text.len(), |i| start + i + 1);
+        let line = &text[start..end];
+        let (indent, content) = indentation(line);
+        let content_start = end - content.len();
+        let blank = content.trim().is_empty();
+
+        let code = if let Some((marker, len)) = fence {
+            if indent < 4 && is_closing_fence(content, marker, len) {
+                fence = None;
+            }
+            true
+        } else if let Some(opening) = opening_fence(content).filter(|_| indent < 4) {
+            fence = Some(opening);
+            true
+        } else if in_indented_code && (indent >= 4 || blank) {
+            true
+        } else if indent >= 4 && previous_blank && !in_list {
+            in_indented_code = true;
+            true
+        } else {
+            in_indented_code = false;
+            false
+        };
+
+        if code || blank {
+            blocks.extend(paragraph.take().map(Block::Paragraph));
+        } else {
+            if is_list_item(content) {
+                in_list = true;
+            } else if indent == 0 && previous_blank {
+                in_list = false;
+            }
+
+            let definition = if paragraph.is_none() && indent < 4 {
+                definition(content)
+            } else {
+                None
+            };
+            match definition {
+                Some((label, destination)) => {
+                    labels.insert(label);
+                    blocks.push(Block::Definition(
+                        content_start + destination.start..content_start + destination.end,
+                    ));
+                }
+                None => match &mut paragraph {
+                    Some(range) => range.end = end,
+                    None => paragraph = Some(start..end),
+                },
+            }
+        }
+
+        previous_blank = blank;
+        start = end;
+    }
+    blocks.extend(paragraph.map(Block::Paragraph));
+    (blocks, labels)
+}
+
+/// The number of columns of indentation (with tab stops of 4) and the rest of the line.
+fn indentation(line: &str) -> (usize, &str) {
+    let mut columns = 0;
+    for (i, b) in line.bytes().enumerate() {
+        match b {
+            b' ' => columns += 1,
+            b'\t' => columns += 4 - columns % 4,
+            _ => return (columns, &line[i..]),
+        }
+    }
+    (columns, "")
+}
+
+fn opening_fence(content: &str) -> Option<(u8, usize)> {
+    let marker = *content.as_bytes().first()?;
+    if marker != b'`' && marker != b'~' {
+        return None;
+    }
+    let len = run_length(content.as_bytes(), 0, marker);
+    if len < 3 || (marker == b'`' && content[len..].contains('`')) {
+        return None;
+    }
+    Some((marker, len))
+}
+
+fn is_closing_fence(content: &str, marker: u8, len: usize) -> bool {
+    let run = run_length(content.as_bytes(), 0, marker);
+    run >= len && content[run..].trim().is_empty()
+}
+
+fn is_list_item(content: &str) -> bool {
+    let bytes = content.as_bytes();
+    let marker_end = match bytes.first() {
+        Some(b'-') | Some(b'*') | Some(b'+') => 1,
+        _ => {
+            let digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+            match bytes.get(digits) {
+                Some(b'.') | Some(b')') if (1..=9).contains(&digits) => digits + 1,
+                _ => return false,
+            }
+        }
+    };
+    matches!(
+        bytes.get(marker_end),
+        None | Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n')
+    )
+}
+
+/// Parse a link reference definition like `[label]: destination "title"`, returning the
+/// normalized label and the range of the destination within the content.
+fn definition(content: &str) -> Option<(String, Range<usize>)> {
+    let bytes = content.as_bytes();
+    if bytes.first() != Some(&b'[') {
+        return None;
+    }
+    let close = find_label_end(bytes, 0, bytes.len())?;
+    if bytes.get(close + 1) != Some(&b':') {
+        return None;
+    }
+    let label = normalize_label(&content[1..close])?;
+
+    let mut i = close + 2;
+    i += run_while(&bytes[i..], |b| b == b' ' || b == b'\t');
+    let destination = if bytes.get(i) == Some(&b'<') {
+        let end = i + 1 + run_while(&bytes[i + 1..], |b| !matches!(b, b'>' | b'<' | b'\n'));
+        if bytes.get(end) != Some(&b'>') {
+            return None;
+        }
+        i + 1..end
+    } else {
+        let end = i + run_while(&bytes[i..], |b| !b.is_ascii_whitespace());
+        i..end
+    };
+    if destination.is_empty() {
+        return None;
+    }
+
+    let rest = content[destination.end..].trim_start_matches('>').trim();
+    if rest.is_empty() || rest.starts_with(&['"', '\'', '('][..]) {
+        Some((label, destination))
+    } else {
+        None
+    }
+}
+
+/// Labels are matched case-insensitively and with whitespace collapsed.
+fn normalize_label(label: &str) -> Option<String> {
+    let words: Vec<_> = label.split_whitespace().collect();
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join(" ").to_lowercase())
+    }
+}
+
+/// Parentheses in link destinations can only be nested this deep, like in cmark. Otherwise text
+/// with many `[a](` would be scanned to the end for each of them.
+const MAX_PAREN_DEPTH: usize = 32;
+
+/// Labels can't be longer than this many characters, see CommonMark.
+const MAX_LABEL_LENGTH: usize = 999;
+
+struct Inlines<'a> {
+    text: &'a str,
+    labels: &'a HashSet<String>,
+    segments: Vec<Segment>,
+    /// The `]` that closes each `[` of the current paragraph.
+    label_ends: HashMap<usize, usize>,
+    /// For closing delimiters like `-->`, the position after which the current paragraph doesn't
+    /// contain them. Searches from later positions don't have to scan the rest of the paragraph
+    /// again, so parsing stays linear with many unclosed openers.
+    unclosed: HashMap<&'static str, usize>,
+}
+
+impl<'a> Inlines<'a> {
+    fn parse(&mut self, range: Range<usize>) {
+        let bytes = self.text.as_bytes();
+        self.label_ends = label_ends(bytes, range.clone());
+        self.unclosed.clear();
+        let end = range.end;
+        let mut text_start = range.start;
+        let mut i = range.start;
+        while i < end {
+            let construct = match bytes[i] {
+                b'\\' => {
+                    // An escaped character can't start a construct
+                    let escaped = matches!(bytes.get(i + 1), Some(b) if b.is_ascii_punctuation());
+                    i += if escaped { 2 } else { 1 };
+                    continue;
+                }
+                b'`' => {
+                    let run = run_length(bytes, i, b'`');
+                    match find_code_span_end(bytes, i + run, end, run) {
+                        Some(span_end) => Some((span_end, None)),
+                        None => {
+                            i += run;
+                            continue;
+                        }
+                    }
+                }
+                b'<' => self.angle(i, end),
+                b'[' => self.link(i, end),
+                b'!' if bytes.get(i + 1) == Some(&b'[') => self.link(i + 1, end),
+                _ => None,
+            };
+            match construct {
+                Some((construct_end, destination)) => {
+                    self.text(text_start..i);
+                    self.segments.extend(destination);
+                    text_start = construct_end;
+                    i = construct_end;
+                }
+                None => i += 1,
+            }
+        }
+        self.text(text_start..end);
+    }
+
+    fn text(&mut self, range: Range<usize>) {
+        if !range.is_empty() {
+            self.segments.push(Segment::Text(range));
+        }
+    }
+
+    /// Autolinks, HTML comments and tags, starting at `<`.
+    fn angle(&mut self, start: usize, end: usize) -> Option<(usize, Option<Segment>)> {
+        let text = self.text;
+        let bytes = &text.as_bytes()[..end];
+
+        if text[start..end].starts_with("<!--") {
+            return self.find("-->", start, end).map(|i| (i + 3, None));
+        }
+
+        let content_end = start
+            + 1
+            + run_while(&bytes[start + 1..], |b| {
+                !b.is_ascii_whitespace() && b != b'<' && b != b'>'
+            });
+        if content_end > start + 1 && bytes.get(content_end) == Some(&b'>') {
+            let destination = start + 1..content_end;
+            let content = &text[destination.clone()];
+            if scheme_length(content).is_some() || content.contains('@') {
+                return Some((content_end + 1, Some(Segment::existing(text, destination))));
+            }
+        }
+
+        let name_start = if bytes.get(start + 1) == Some(&b'/') {
+            start + 2
+        } else {
+            start + 1
+        };
+        if !matches!(bytes.get(name_start), Some(b) if b.is_ascii_alphabetic()) {
+            return None;
+        }
+        let name_end = name_start
+            + run_while(&bytes[name_start..], |b| {
+                b.is_ascii_alphanumeric() || b == b'-'
+            });
+        match bytes.get(name_end) {
+            Some(b) if b.is_ascii_whitespace() || *b == b'/' || *b == b'>' => {}
+            _ => return None,
+        }
+        let tag_end = self.find(">", name_end, end)? + 1;
+
+        let opening = name_start == start + 1 && bytes[tag_end - 2] != b'/';
+        let closing = match text[name_start..name_end].to_ascii_lowercase().as_str() {
+            "a" => Some("</a"),
+            "code" => Some("</code"),
+            "pre" => Some("</pre"),
+            _ => None,
+        };
+        if let Some(closing) = closing.filter(|_| opening) {
+            // Skip the contents of the element too
+            if let Some(closing_start) = self.find(closing, tag_end, end) {
+                if let Some(i) = self.find(">", closing_start, end) {
+                    return Some((i + 1, None));
+                }
+            }
+        }
+        Some((tag_end, None))
+    }
+
+    /// Inline links and images, and reference links, starting at `[`.
+    fn link(&mut self, start: usize, end: usize) -> Option<(usize, Option<Segment>)> {
+        let bytes = self.text.as_bytes();
+        let close = *self.label_ends.get(&start)?;
+
+        match bytes.get(close + 1) {
+            Some(b'(') if close + 1 < end => {
+                let (link_end, destination) = self.inline_destination(close + 1, end)?;
+                let destination = if destination.is_empty() {
+                    None
+                } else {
+                    Some(Segment::existing(self.text, destination))
+                };
+                Some((link_end, destination))
+            }
+            Some(b'[') if close + 1 < end => {
+                let label_end = *self.label_ends.get(&(close + 1))?;
+                let label = if label_end == close + 2 {
+                    &self.text[start + 1..close]
+                } else {
+                    &self.text[close + 2..label_end]
+                };
+                if self.is_defined(label) {
+                    Some((label_end + 1, None))
+                } else {
+                    None
+                }
+            }
+            _ if self.is_defined(&self.text[start + 1..close]) => Some((close + 1, None)),
+            _ => None,
+        }
+    }
+
+    fn is_defined(&self, label: &str) -> bool {
+        if self.labels.is_empty() || label.chars().nth(MAX_LABEL_LENGTH).is_some() {
+            return false;
+        }
+        match normalize_label(label) {
+            Some(label) => self.labels.contains(&label),
+            None => false,
+        }
+    }
+
+    /// Parse `(destination "title")` starting at `(`, returning the end and the destination.
+    fn inline_destination(&mut self, start: usize, end: usize) -> Option<(usize, Range<usize>)> {
+        let bytes = &self.text.as_bytes()[..end];
+        let mut i = start + 1;
+        i += run_while(&bytes[i..], |b| b.is_ascii_whitespace());
+
+        let destination = if bytes.get(i) == Some(&b'<') {
+            let close = i + 1 + run_while(&bytes[i + 1..], |b| !matches!(b, b'>' | b'<' | b'\n'));
+            if bytes.get(close) != Some(&b'>') {
+                return None;
+            }
+            let destination = i + 1..close;
+            i = close + 1;
+            destination
+        } else {
+            let destination_start = i;
+            let mut depth = 0;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'\\' => i += 1,
+                    b'(' if depth == MAX_PAREN_DEPTH => return None,
+                    b'(' => depth += 1,
+                    b')' if depth == 0 => break,
+                    b')' => depth -= 1,
+                    b if b.is_ascii_whitespace() || b.is_ascii_control() => break,
+                    _ => {}
+                }
+                i += 1;
+            }
+            // A backslash at the end skips past it
+            i = i.min(bytes.len());
+            destination_start..i
+        };
+
+        i += run_while(&bytes[i..], |b| b.is_ascii_whitespace());
+        if let Some(&quote) = bytes.get(i).filter(|b| matches!(b, b'"' | b'\'' | b'(')) {
+            let closing = match quote {
+                b'"' => "\"",
+                b'\'' => "'",
+                _ => ")",
+            };
+            i = self.find(closing, i + 1, end)? + 1;
+            i += run_while(&bytes[i..], |b| b.is_ascii_whitespace());
+        }
+        if bytes.get(i) == Some(&b')') {
+            Some((i + 1, destination))
+        } else {
+            None
+        }
+    }
+
+    /// Find the closing delimiter (ASCII case-insensitively) from the position to the end.
+    fn find(&mut self, closing: &'static str, from: usize, end: usize) -> Option<usize> {
+        if matches!(self.unclosed.get(closing), Some(&unclosed) if unclosed <= from) {
+            return None;
+        }
+        let bytes = &self.text.as_bytes()[..end];
+        let closing_bytes = closing.as_bytes();
+        let mut i = from;
+        while let Some(j) = memchr(closing_bytes[0], &bytes[i..]) {
+            let candidate = &bytes[i + j..];
+            if candidate.len() >= closing.len()
+                && candidate[..closing.len()].eq_ignore_ascii_case(closing_bytes)
+            {
+                return Some(i + j);
+            }
+            i += j + 1;
+        }
+        let unclosed = self.unclosed.entry(closing).or_insert(from);
+        *unclosed = (*unclosed).min(from);
+        None
+    }
+}
+
+/// The `]` that closes each `[` in the range, like `find_label_end` but in one pass.
+fn label_ends(bytes: &[u8], range: Range<usize>) -> HashMap<usize, usize> {
+    let mut ends = HashMap::new();
+    let mut open = Vec::new();
+    let mut i = range.start;
+    while i < range.end {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'[' => open.push(i),
+            b']' => {
+                if let Some(start) = open.pop() {
+                    ends.insert(start, i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    ends
+}
+
+/// Find the `]` that closes the `[` at `start`, allowing nested brackets.
+fn find_label_end(bytes: &[u8], start: usize, end: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = start + 1;
+    while i < end {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'[' => depth += 1,
+            b']' if depth == 0 => return Some(i),
+            b']' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Find the end of a code span with a backtick run of the specified length.
+fn find_code_span_end(bytes: &[u8], mut i: usize, end: usize, run: usize) -> Option<usize> {
+    while i < end {
+        i += memchr(b'`', &bytes[i..end])?;
+        let closing = run_length(bytes, i, b'`');
+        if closing == run {
+            return Some(i + closing);
+        }
+        i += closing;
+    }
+    None
+}
+
+fn run_length(bytes: &[u8], start: usize, b: u8) -> usize {
+    run_while(&bytes[start..], |c| c == b)
+}
+
+fn run_while<F: Fn(u8) -> bool>(bytes: &[u8], f: F) -> usize {
+    bytes.iter().take_while(|&&b| f(b)).count()
+}
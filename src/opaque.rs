@@ -0,0 +1,228 @@
+//! Scanning of opaque URIs, i.e. URIs without an authority such as `mailto:foo@example.org`.
+//!
+//! Unlike URLs with an authority, the part after the `:` doesn't have a common structure, so there
+//! are different syntaxes depending on the scheme, see `OpaqueSyntax`.
+
+use crate::domains::find_authority_end;
+use crate::email::EmailScanner;
+use crate::scheme::OpaqueSyntax;
+use crate::url::{find_path_end, find_url_end};
+
+/// Find the end of an opaque URI. The input starts after the `:` following the scheme.
+///
+/// Returns `None` if the input doesn't match the syntax.
+pub(crate) fn find_opaque_end(
+    s: &str,
+    syntax: OpaqueSyntax,
+    quote: Option<char>,
+    iri_parsing_enabled: bool,
+) -> Option<usize> {
+    let end = match syntax {
+        OpaqueSyntax::Mailto => find_mailto_end(s, quote, iri_parsing_enabled),
+        OpaqueSyntax::Tel => find_tel_end(s),
+        OpaqueSyntax::Urn => find_urn_end(s, quote, iri_parsing_enabled),
+        OpaqueSyntax::Query => {
+            if s.starts_with('?') {
+                find_url_end(s, quote, iri_parsing_enabled)
+            } else {
+                None
+            }
+        }
+        OpaqueSyntax::Xmpp => find_xmpp_end(s, quote, iri_parsing_enabled),
+        OpaqueSyntax::Data => find_data_end(s, quote, iri_parsing_enabled),
+        OpaqueSyntax::Path => {
+            if s.starts_with('/') {
+                // `foo:/bar` is a URI with an absolute path, but in text it's more likely to be
+                // something else.
+                None
+            } else {
+                find_path_end(s, quote, iri_parsing_enabled)
+            }
+        }
+    };
+
+    // Only the scheme is not enough, e.g. `magnet:?`
+    match end {
+        Some(end) if end > 0 => Some(end),
+        _ => None,
+    }
+}
+
+/// `mailto:` followed by one or more addresses separated by `,`, then an optional query.
+fn find_mailto_end(s: &str, quote: Option<char>, iri_parsing_enabled: bool) -> Option<usize> {
+    let mut end = find_address_end(s, iri_parsing_enabled)?;
+
+    while s[end..].starts_with(',') {
+        match find_address_end(&s[end + 1..], iri_parsing_enabled) {
+            Some(next) => end += 1 + next,
+            None => break,
+        }
+    }
+
+    let query = find_url_end(&s[end..], quote, iri_parsing_enabled)?;
+    Some(end + query)
+}
+
+/// Find the end of an email address at the start of the input. Unlike in `EmailScanner` we scan
+/// forwards here, as we know where the address starts.
+fn find_address_end(s: &str, iri_parsing_enabled: bool) -> Option<usize> {
+    let mut at = None;
+    let mut atom_boundary = true;
+    for (i, c) in s.char_indices() {
+        if EmailScanner::local_atom_allowed(c) && (c.is_ascii() || iri_parsing_enabled) {
+            atom_boundary = false;
+        } else if c == '.' {
+            if atom_boundary {
+                return None;
+            }
+            atom_boundary = true;
+        } else if c == '@' {
+            if atom_boundary {
+                // Empty local part or ending with a `.`
+                return None;
+            }
+            at = Some(i);
+            break;
+        } else {
+            return None;
+        }
+    }
+
+    let after = at? + 1;
+    if s[after..].starts_with('[') {
+        // Same as for emails, address literals are not supported
+        return None;
+    }
+    match find_authority_end(&s[after..], false, true, false, iri_parsing_enabled) {
+        (Some(end), _) if end > 0 => Some(after + end),
+        _ => None,
+    }
+}
+
+/// `tel:` followed by a global (`+` prefix) or local number, with optional parameters like
+/// `;ext=1234`. Visual separators `-`, `.`, `(` and `)` are allowed between digits.
+fn find_tel_end(s: &str) -> Option<usize> {
+    let number = s.strip_prefix('+').unwrap_or(s);
+    let offset = s.len() - number.len();
+
+    let mut end = None;
+    let mut digits = 0;
+    for (i, c) in number.char_indices() {
+        match c {
+            '0'..='9' => {
+                digits += 1;
+                end = Some(offset + i + 1);
+            }
+            '-' | '.' | '(' | ')' => {}
+            _ => break,
+        }
+    }
+
+    // Not even emergency numbers are shorter than that
+    if digits < 3 {
+        return None;
+    }
+    let mut end = end?;
+
+    // Parameters: `;name` or `;name=value`
+    while s[end..].starts_with(';') {
+        let param = &s[end + 1..];
+        let name = param
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .unwrap_or(param.len());
+        if name == 0 {
+            break;
+        }
+
+        let mut param_end = name;
+        if param[name..].starts_with('=') {
+            let value = &param[name + 1..];
+            let mut value_end = 0;
+            for (i, c) in value.char_indices() {
+                match c {
+                    'a'..='z' | 'A'..='Z' | '0'..='9' => value_end = i + 1,
+                    '-' | '_' | '.' | '!' | '~' | '*' | '\'' | '(' | ')' | '%' | '+' | '/' => {}
+                    _ => break,
+                }
+            }
+            if value_end == 0 {
+                break;
+            }
+            param_end = name + 1 + value_end;
+        }
+
+        end += 1 + param_end;
+    }
+
+    Some(end)
+}
+
+/// `urn:` followed by a namespace identifier (NID) and a namespace specific string (NSS), separated
+/// by `:`. The NID is 2 to 32 letters, digits or hyphens, and can't start or end with a hyphen.
+fn find_urn_end(s: &str, quote: Option<char>, iri_parsing_enabled: bool) -> Option<usize> {
+    let nid = s
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(s.len());
+    if !(2..=32).contains(&nid) || s.starts_with('-') || s[..nid].ends_with('-') {
+        return None;
+    }
+    if !s[nid..].starts_with(':') {
+        return None;
+    }
+
+    let after = nid + 1;
+    match find_path_end(&s[after..], quote, iri_parsing_enabled) {
+        Some(end) if end > 0 => Some(after + end),
+        _ => None,
+    }
+}
+
+/// `xmpp:` followed by a Jabber ID (`node@domain/resource`) and an optional query. The node and
+/// resource are optional.
+fn find_xmpp_end(s: &str, quote: Option<char>, iri_parsing_enabled: bool) -> Option<usize> {
+    let mut domain_start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            // Not allowed in a node, see "nodeprep" in RFC 6122
+            '"' | '&' | '\'' | '/' | ':' | '<' | '>' | '?' | '#' => break,
+            '@' => {
+                if i > 0 {
+                    domain_start = i + 1;
+                }
+                break;
+            }
+            c if c.is_whitespace() || c.is_control() => break,
+            c if !c.is_ascii() && !iri_parsing_enabled => break,
+            _ => {}
+        }
+    }
+
+    let domain = &s[domain_start..];
+    let domain_end = match find_authority_end(domain, false, true, false, iri_parsing_enabled) {
+        (Some(end), _) if end > 0 => domain_start + end,
+        _ => return None,
+    };
+
+    let rest = find_url_end(&s[domain_end..], quote, iri_parsing_enabled)?;
+    Some(domain_end + rest)
+}
+
+/// `data:` followed by an optional media type and `;base64`, then `,` and the data.
+fn find_data_end(s: &str, quote: Option<char>, iri_parsing_enabled: bool) -> Option<usize> {
+    let comma = s.find(|c: char| {
+        !(c.is_ascii_alphanumeric()
+            || matches!(
+                c,
+                '!' | '#' | '$' | '&' | '-' | '^' | '_' | '.' | '+' | '/' | ';' | '='
+            ))
+    })?;
+    if !s[comma..].starts_with(',') {
+        return None;
+    }
+
+    let after = comma + 1;
+    match find_path_end(&s[after..], quote, iri_parsing_enabled) {
+        Some(end) if end > 0 => Some(after + end),
+        _ => None,
+    }
+}
@@ -0,0 +1,81 @@
+use crate::finder::{InputFormat, LinkFinder, LinkKind};
+use crate::reference::ReferenceRules;
+use crate::scheme::SchemePolicy;
+use crate::tag::TagRules;
+
+/// The options of a [`LinkFinder`] as a plain struct, e.g. for loading them from a config file
+/// with the `serde` feature.
+///
+/// Fields that are missing when deserializing get their default value, which is the same as for
+/// [`LinkFinder::new`]. The TLD validator is a function, so it can't be configured like this: set
+/// it on the finder after creating it. It's also not part of [`LinkFinder::options`], so it's
+/// lost when converting a finder to options and back.
+///
+/// ```
+/// use linkify::{LinkFinder, LinkFinderOptions, LinkKind};
+///
+/// let mut options = LinkFinderOptions::default();
+/// options.kinds = vec![LinkKind::Url];
+/// options.url_must_have_scheme = false;
+///
+/// let finder = LinkFinder::from(options);
+/// let links: Vec<_> = finder.links("example.org and a@example.org").collect();
+/// assert_eq!(1, links.len());
+/// assert_eq!(false, finder.options().url_must_have_scheme);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[non_exhaustive]
+pub struct LinkFinderOptions {
+    /// The kinds of links to find, see [`LinkFinder::kinds`].
+    pub kinds: Vec<LinkKind>,
+    /// See [`LinkFinder::url_must_have_scheme`].
+    pub url_must_have_scheme: bool,
+    /// See [`LinkFinder::url_can_be_iri`].
+    pub url_can_be_iri: bool,
+    /// See [`LinkFinder::email_domain_must_have_dot`].
+    pub email_domain_must_have_dot: bool,
+    /// See [`LinkFinder::input_format`].
+    pub input_format: InputFormat,
+    /// See [`LinkFinder::existing_links`].
+    pub existing_links: bool,
+    /// See [`LinkFinder::defanged`].
+    pub defanged: bool,
+    /// See [`LinkFinder::mention_rules`].
+    pub mention_rules: TagRules,
+    /// See [`LinkFinder::hashtag_rules`].
+    pub hashtag_rules: TagRules,
+    /// See [`LinkFinder::reference_rules`].
+    pub reference_rules: ReferenceRules,
+    /// See [`LinkFinder::scheme_policy`].
+    pub scheme_policy: SchemePolicy,
+}
+
+impl Default for LinkFinderOptions {
+    fn default() -> Self {
+        LinkFinder::new().options()
+    }
+}
+
+impl From<LinkFinderOptions> for LinkFinder {
+    fn from(options: LinkFinderOptions) -> LinkFinder {
+        let mut finder = LinkFinder::new();
+        finder
+            .kinds(&options.kinds)
+            .url_must_have_scheme(options.url_must_have_scheme)
+            .url_can_be_iri(options.url_can_be_iri)
+            .email_domain_must_have_dot(options.email_domain_must_have_dot)
+            .input_format(options.input_format)
+            .existing_links(options.existing_links)
+            .defanged(options.defanged)
+            .mention_rules(options.mention_rules)
+            .hashtag_rules(options.hashtag_rules)
+            .reference_rules(options.reference_rules)
+            .scheme_policy(options.scheme_policy);
+        finder
+    }
+}
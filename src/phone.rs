@@ -0,0 +1,162 @@
+use std::ops::Range;
+
+use crate::scanner::Scanner;
+
+/// Scan for phone numbers starting from the trigger character, the first digit of a number.
+///
+/// Accepts international numbers in E.164 format with a leading `+` (e.g. `+41 44 668 18 00`),
+/// and national numbers with separators (e.g. `(555) 123-4567` or `020 7946 0958`). Digits can be
+/// separated by a single space, `.` or `-`, and groups can be in parentheses.
+///
+/// To not find numbers that are something else, the following are rejected: numbers with fewer
+/// than 7 or more than 15 digits, and for national numbers, numbers without separators, IPv4
+/// addresses, dates like `2024-01-31`, decimal numbers and numbers with thousands separators.
+pub struct PhoneScanner;
+
+impl Scanner for PhoneScanner {
+    fn scan(&self, s: &str, digit: usize) -> Option<Range<usize>> {
+        let bytes = s.as_bytes();
+        let international = digit > 0 && bytes[digit - 1] == b'+';
+        let start = if digit > 0 && (international || bytes[digit - 1] == b'(') {
+            digit - 1
+        } else {
+            digit
+        };
+        let mut before = s[..start].chars().rev();
+        match before.next() {
+            Some(c) if c.is_alphanumeric() || "+.-/_@#$".contains(c) => return None,
+            // Part of a longer number that was rejected, e.g. `+1234 5678 9012 3456`
+            Some(' ') if matches!(before.next(), Some(c) if c.is_ascii_digit() || c == ')') => {
+                return None
+            }
+            _ => {}
+        }
+
+        let number = parse(&bytes[start..])?;
+        let end = start + number.len;
+        if let Some(c) = s[end..].chars().next() {
+            if c.is_alphanumeric() || c == '@' || c == '_' {
+                return None;
+            }
+        }
+
+        let digits: usize = number.groups.iter().sum();
+        if !(7..=15).contains(&digits) {
+            return None;
+        }
+        if international {
+            // Country codes don't start with 0
+            if bytes[digit] == b'0' {
+                return None;
+            }
+        } else if !is_national(&number) {
+            return None;
+        }
+        Some(start..end)
+    }
+}
+
+struct Number {
+    len: usize,
+    /// The number of digits of each group
+    groups: Vec<usize>,
+    /// The separators between the groups, and `(` for groups in parentheses
+    separators: Vec<u8>,
+}
+
+fn parse(bytes: &[u8]) -> Option<Number> {
+    let mut number = Number {
+        len: 0,
+        groups: Vec::new(),
+        separators: Vec::new(),
+    };
+    let mut i = if bytes[0] == b'+' { 1 } else { 0 };
+    loop {
+        let parenthesized = bytes.get(i) == Some(&b'(');
+        let group_start = if parenthesized { i + 1 } else { i };
+        let digits = bytes[group_start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits == 0 {
+            break;
+        }
+        let mut group_end = group_start + digits;
+        if parenthesized {
+            if bytes.get(group_end) != Some(&b')') {
+                break;
+            }
+            group_end += 1;
+            number.separators.push(b'(');
+        }
+        number.groups.push(digits);
+        number.len = group_end;
+
+        i = group_end;
+        match bytes.get(i) {
+            Some(&separator @ b' ') | Some(&separator @ b'.') | Some(&separator @ b'-') => {
+                match bytes.get(i + 1) {
+                    Some(b) if b.is_ascii_digit() || *b == b'(' => {
+                        number.separators.push(separator);
+                        i += 1;
+                    }
+                    _ => break,
+                }
+            }
+            // A group directly after a group in parentheses, e.g. `(0)20`
+            Some(b) if parenthesized && b.is_ascii_digit() => {}
+            _ => break,
+        }
+    }
+    if number.groups.is_empty() {
+        None
+    } else {
+        Some(number)
+    }
+}
+
+fn is_national(number: &Number) -> bool {
+    let groups = &number.groups;
+    let separators = &number.separators;
+    if separators.is_empty() {
+        return false;
+    }
+    let dots = separators.iter().filter(|&&s| s == b'.').count();
+    if dots > 0 && (dots != separators.len() || groups.len() < 3) {
+        // Dots can't be mixed with other separators, and `3.1415926` is a decimal number
+        return false;
+    }
+    if dots == 3 && groups.iter().all(|&digits| digits <= 3) {
+        // IPv4 address
+        return false;
+    }
+    if separators[0] != b' ' && matches!(groups[..], [4, 2, 2] | [2, 2, 4]) {
+        // Date
+        return false;
+    }
+    if separators.iter().all(|&s| s == b' ')
+        && groups[0] <= 3
+        && groups[1..].iter().all(|&d| d == 3)
+    {
+        // Thousands separators, e.g. `1 000 000`
+        return false;
+    }
+    true
+}
+
+/// Normalize a phone number found by the scanner for use in a `tel:` URI, e.g. `+1 (555) 123-4567`
+/// becomes `+15551234567`.
+///
+/// A trunk prefix in parentheses in international numbers is removed, e.g. `+44 (0)20 7946 0958`
+/// becomes `+442079460958`.
+pub(crate) fn normalize(number: &str) -> String {
+    let number = if number.starts_with('+') {
+        number.replacen("(0)", "", 1)
+    } else {
+        number.to_string()
+    };
+    number
+        .chars()
+        .filter(|&c| c == '+' || c.is_ascii_digit())
+        .collect()
+}
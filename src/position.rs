@@ -0,0 +1,181 @@
+use std::ops::Range;
+
+/// A position in a text as a line and a column, both starting at 0.
+///
+/// Depending on the method of [`LineIndex`] that returned it, the column is in bytes (UTF-8 code
+/// units) or in UTF-16 code units (as used by JavaScript and the Language Server Protocol).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    /// Create a position from a line and a column, both starting at 0.
+    pub fn new(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+
+    /// The line, starting at 0.
+    #[inline]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column within the line, starting at 0.
+    #[inline]
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+/// Converts byte indices within a text (such as [`Link::start`](crate::Link::start) and
+/// [`Link::end`](crate::Link::end)) into lines and columns, and into offsets in characters or
+/// UTF-16 code units.
+///
+/// The index is built once per text, after that each conversion is a binary search instead of a
+/// scan of the text. Lines end with `\n`, `\r\n` or `\r`.
+///
+/// ```
+/// use linkify::{LineIndex, LinkFinder, Position};
+///
+/// let input = "Hello\nSee 🦀 http://example.org";
+/// let index = LineIndex::new(input);
+/// let finder = LinkFinder::new();
+/// let link = finder.links(input).next().unwrap();
+///
+/// assert_eq!(Position::new(1, 9), index.position(link.start()));
+/// assert_eq!(Position::new(1, 7), index.position_utf16(link.start()));
+/// assert_eq!(Position::new(1, 25), index.position_utf16(link.end()));
+/// assert_eq!(12, index.char_offset(link.start()));
+/// assert_eq!(13, index.utf16_offset(link.start()));
+/// ```
+///
+/// The byte indices passed to the methods must be on character boundaries of the text (which is
+/// the case for links and spans), otherwise the results are not meaningful.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LineIndex {
+    len: usize,
+    /// The byte index of the start of each line
+    line_starts: Vec<usize>,
+    /// The non-ASCII characters of the text, to convert between bytes and other units
+    wide_chars: Vec<WideChar>,
+}
+
+/// A non-ASCII character with the number of bytes more than characters and UTF-16 code units up
+/// to and including it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct WideChar {
+    start: usize,
+    char_diff: usize,
+    utf16_diff: usize,
+}
+
+impl LineIndex {
+    /// Build the index for the text.
+    pub fn new(text: &str) -> LineIndex {
+        let mut line_starts = vec![0];
+        let mut wide_chars = Vec::new();
+        let mut char_diff = 0;
+        let mut utf16_diff = 0;
+        let bytes = text.as_bytes();
+        for (i, c) in text.char_indices() {
+            match c {
+                '\n' => line_starts.push(i + 1),
+                '\r' if bytes.get(i + 1) != Some(&b'\n') => line_starts.push(i + 1),
+                _ if !c.is_ascii() => {
+                    char_diff += c.len_utf8() - 1;
+                    utf16_diff += c.len_utf8() - c.len_utf16();
+                    wide_chars.push(WideChar {
+                        start: i,
+                        char_diff,
+                        utf16_diff,
+                    });
+                }
+                _ => {}
+            }
+        }
+        LineIndex {
+            len: text.len(),
+            line_starts,
+            wide_chars,
+        }
+    }
+
+    /// The number of lines of the text. A text ending with a line break has an empty last line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The position of the byte index, with the column in bytes.
+    ///
+    /// Panics if the index is greater than the length of the text.
+    pub fn position(&self, index: usize) -> Position {
+        let line = self.line(index);
+        Position::new(line, index - self.line_starts[line])
+    }
+
+    /// The position of the byte index, with the column in UTF-16 code units.
+    ///
+    /// Panics if the index is greater than the length of the text.
+    pub fn position_utf16(&self, index: usize) -> Position {
+        let line = self.line(index);
+        let column = self.utf16_offset(index) - self.utf16_offset(self.line_starts[line]);
+        Position::new(line, column)
+    }
+
+    /// The positions of the byte range (e.g. of a link), with the columns in bytes.
+    pub fn range(&self, range: Range<usize>) -> Range<Position> {
+        self.position(range.start)..self.position(range.end)
+    }
+
+    /// The positions of the byte range (e.g. of a link), with the columns in UTF-16 code units.
+    pub fn range_utf16(&self, range: Range<usize>) -> Range<Position> {
+        self.position_utf16(range.start)..self.position_utf16(range.end)
+    }
+
+    /// The number of characters (Unicode scalar values) before the byte index.
+    ///
+    /// Panics if the index is greater than the length of the text.
+    pub fn char_offset(&self, index: usize) -> usize {
+        index - self.last_wide_char_before(index).map_or(0, |c| c.char_diff)
+    }
+
+    /// The number of UTF-16 code units before the byte index, e.g. for indexing a JavaScript
+    /// string.
+    ///
+    /// Panics if the index is greater than the length of the text.
+    pub fn utf16_offset(&self, index: usize) -> usize {
+        index
+            - self
+                .last_wide_char_before(index)
+                .map_or(0, |c| c.utf16_diff)
+    }
+
+    fn line(&self, index: usize) -> usize {
+        self.check(index);
+        match self.line_starts.binary_search(&index) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        }
+    }
+
+    /// The last non-ASCII character before the byte index, if any.
+    fn last_wide_char_before(&self, index: usize) -> Option<&WideChar> {
+        self.check(index);
+        let count = match self.wide_chars.binary_search_by_key(&index, |c| c.start) {
+            Ok(i) | Err(i) => i,
+        };
+        count.checked_sub(1).map(|i| &self.wide_chars[i])
+    }
+
+    fn check(&self, index: usize) {
+        assert!(
+            index <= self.len,
+            "index {} is out of bounds of text with length {}",
+            index,
+            self.len
+        );
+    }
+}
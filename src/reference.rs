@@ -0,0 +1,336 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::scanner::Scanner;
+
+/// The parts of a reference to an issue, ticket or commit, see
+/// [`Link::reference`](crate::Link::reference).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Reference<'t> {
+    /// An issue or pull request like `#123`, `owner/repo#123` or `GH-123`. The owner and
+    /// repository are `None` if they are not part of the reference.
+    Issue {
+        /// The owner of the repository, e.g. `owner` in `owner/repo#123`
+        owner: Option<&'t str>,
+        /// The name of the repository, e.g. `repo` in `owner/repo#123`
+        repo: Option<&'t str>,
+        /// The number of the issue, e.g. `123`
+        number: u64,
+    },
+    /// A Jira-style ticket like `PROJ-1234`.
+    Ticket {
+        /// The project key, e.g. `PROJ`
+        project: &'t str,
+        /// The number of the ticket within the project, e.g. `1234`
+        number: u64,
+    },
+    /// A commit hash like `3fcb00f`, abbreviated or full.
+    Commit {
+        /// The hash in hexadecimal
+        sha: &'t str,
+    },
+}
+
+impl<'t> Reference<'t> {
+    /// Parse a reference that was found by the scanner.
+    pub(crate) fn parse(text: &'t str) -> Option<Reference<'t>> {
+        if let Some(hash) = text.find('#') {
+            let number = text[hash + 1..].parse().ok()?;
+            let (owner, repo) = match text[..hash].find('/') {
+                Some(slash) => (Some(&text[..slash]), Some(&text[slash + 1..hash])),
+                None => (None, None),
+            };
+            Some(Reference::Issue {
+                owner,
+                repo,
+                number,
+            })
+        } else if let Some(hyphen) = text.find('-') {
+            let number = text[hyphen + 1..].parse().ok()?;
+            match &text[..hyphen] {
+                "GH" => Some(Reference::Issue {
+                    owner: None,
+                    repo: None,
+                    number,
+                }),
+                project => Some(Reference::Ticket { project, number }),
+            }
+        } else {
+            Some(Reference::Commit { sha: text })
+        }
+    }
+}
+
+/// Rules for which references to find and how to turn them into URLs.
+///
+/// Use this with [`LinkFinder::reference_rules`](crate::LinkFinder::reference_rules). By default,
+/// issues (`#123`, `owner/repo#123` and `GH-123`) and commit hashes (7 to 40 lowercase hexadecimal
+/// characters with at least one digit and one letter) are found. Jira-style tickets like
+/// `PROJ-1234` are only found for the configured projects, because otherwise things like `UTF-8`
+/// or `SHA-256` would be tickets.
+///
+/// URL templates contain placeholders in braces that are replaced with the parts of a reference:
+/// `{owner}`, `{repo}` and `{n}` for issues, `{project}` and `{n}` for tickets and `{sha}` for
+/// commits. For references without an owner and repository, the ones set with
+/// [`repository`](ReferenceRules::repository) are used.
+///
+/// ```
+/// use linkify::ReferenceRules;
+///
+/// let rules = ReferenceRules::new()
+///     .repository("robinst", "linkify")
+///     .issue_url("https://github.com/{owner}/{repo}/issues/{n}")
+///     .projects(&["PROJ"])
+///     .ticket_url("https://example.atlassian.net/browse/{project}-{n}");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ReferenceRules {
+    issues: bool,
+    commits: bool,
+    projects: Vec<String>,
+    repository: Option<(String, String)>,
+    issue_url: Option<String>,
+    ticket_url: Option<String>,
+    commit_url: Option<String>,
+}
+
+impl ReferenceRules {
+    /// Create the default rules: issues and commits are found, tickets and URL templates are not
+    /// configured.
+    pub fn new() -> ReferenceRules {
+        ReferenceRules {
+            issues: true,
+            commits: true,
+            projects: Vec::new(),
+            repository: None,
+            issue_url: None,
+            ticket_url: None,
+            commit_url: None,
+        }
+    }
+
+    /// Set whether issues like `#123`, `owner/repo#123` and `GH-123` are found.
+    pub fn issues(mut self, issues: bool) -> ReferenceRules {
+        self.issues = issues;
+        self
+    }
+
+    /// Set whether commit hashes like `3fcb00f` are found.
+    pub fn commits(mut self, commits: bool) -> ReferenceRules {
+        self.commits = commits;
+        self
+    }
+
+    /// Set the project keys of Jira-style tickets to find, e.g. `PROJ` for `PROJ-1234`.
+    pub fn projects(mut self, projects: &[&str]) -> ReferenceRules {
+        self.projects = projects.iter().map(|p| p.to_string()).collect();
+        self
+    }
+
+    /// Set the repository for references that don't include one, e.g. `#123`.
+    pub fn repository(mut self, owner: &str, repo: &str) -> ReferenceRules {
+        self.repository = Some((owner.to_string(), repo.to_string()));
+        self
+    }
+
+    /// Set the URL template for issues, e.g. `https://github.com/{owner}/{repo}/issues/{n}`.
+    pub fn issue_url(mut self, template: &str) -> ReferenceRules {
+        self.issue_url = Some(template.to_string());
+        self
+    }
+
+    /// Set the URL template for tickets, e.g. `https://example.atlassian.net/browse/{project}-{n}`.
+    pub fn ticket_url(mut self, template: &str) -> ReferenceRules {
+        self.ticket_url = Some(template.to_string());
+        self
+    }
+
+    /// Set the URL template for commits, e.g. `https://github.com/{owner}/{repo}/commit/{sha}`.
+    pub fn commit_url(mut self, template: &str) -> ReferenceRules {
+        self.commit_url = Some(template.to_string());
+        self
+    }
+
+    /// The URL for the reference according to the templates.
+    ///
+    /// Returns `None` if there's no template for the kind of reference, or if the template needs
+    /// an owner and repository but there are none.
+    pub fn url(&self, reference: &Reference<'_>) -> Option<String> {
+        let default_repository = self
+            .repository
+            .as_ref()
+            .map(|(owner, repo)| (owner.as_str(), repo.as_str()));
+        let (template, repository) = match *reference {
+            Reference::Issue {
+                owner: Some(owner),
+                repo: Some(repo),
+                ..
+            } => (&self.issue_url, Some((owner, repo))),
+            Reference::Issue { .. } => (&self.issue_url, default_repository),
+            Reference::Ticket { .. } => (&self.ticket_url, None),
+            Reference::Commit { .. } => (&self.commit_url, default_repository),
+        };
+        let mut url = template.clone()?;
+        if url.contains("{owner}") || url.contains("{repo}") {
+            let (owner, repo) = repository?;
+            url = url.replace("{owner}", owner).replace("{repo}", repo);
+        }
+        match *reference {
+            Reference::Issue { number, .. } => url = url.replace("{n}", &number.to_string()),
+            Reference::Ticket { project, number } => {
+                url = url
+                    .replace("{project}", project)
+                    .replace("{n}", &number.to_string())
+            }
+            Reference::Commit { sha } => url = url.replace("{sha}", sha),
+        }
+        Some(url)
+    }
+}
+
+impl Default for ReferenceRules {
+    fn default() -> Self {
+        ReferenceRules::new()
+    }
+}
+
+/// Scan for references, starting from `#` for issues and from a digit for the others.
+pub struct ReferenceScanner {
+    pub rules: Arc<ReferenceRules>,
+}
+
+impl ReferenceScanner {
+    fn scan_issue(&self, s: &str, hash: usize) -> Option<Range<usize>> {
+        if !self.rules.issues {
+            return None;
+        }
+        let digits = count_digits(&s[hash + 1..]);
+        let end = hash + 1 + digits;
+        if !is_number(&s[hash + 1..end]) || !is_end(s, end) {
+            return None;
+        }
+
+        // `owner/repo#123`, where names can contain letters, digits, `_`, `.` and `-`
+        let name_start = |end: usize| {
+            s[..end]
+                .rfind(|c: char| !(c.is_ascii_alphanumeric() || "_.-".contains(c)))
+                .map_or(0, |i| i + 1)
+        };
+        let repo_start = name_start(hash);
+        let start = if repo_start > 0 && repo_start < hash && s[..repo_start].ends_with('/') {
+            let owner_start = name_start(repo_start - 1);
+            if owner_start < repo_start - 1 {
+                owner_start
+            } else {
+                hash
+            }
+        } else {
+            hash
+        };
+        // Not after words like in `C#1`, entities like `&#123;` or paths like `example.org/#1`
+        if !is_start(s, start) {
+            return None;
+        }
+        Some(start..end)
+    }
+
+    fn scan_key(&self, s: &str, digit: usize) -> Option<Range<usize>> {
+        // Keys are uppercase letters followed by uppercase letters, digits or `_`
+        let key_start = s[..digit - 1]
+            .rfind(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+            .map_or(0, |i| i + 1);
+        let key = &s[key_start..digit - 1];
+        if !key.starts_with(|c: char| c.is_ascii_uppercase()) || !is_start(s, key_start) {
+            return None;
+        }
+        let is_issue = key == "GH" && self.rules.issues;
+        if !is_issue && !self.rules.projects.iter().any(|p| p == key) {
+            return None;
+        }
+        let end = digit + count_digits(&s[digit..]);
+        if !is_number(&s[digit..end]) || !is_end(s, end) {
+            return None;
+        }
+        Some(key_start..end)
+    }
+
+    fn scan_commit(&self, s: &str, digit: usize) -> Option<Range<usize>> {
+        const MAX_LENGTH: usize = 40;
+        let is_hex = |b: &u8| b.is_ascii_digit() || (b'a'..=b'f').contains(b);
+        let bytes = s.as_bytes();
+        // Limit the search so that long hexadecimal strings are not scanned again for each digit
+        let before = bytes[..digit]
+            .iter()
+            .rev()
+            .take(MAX_LENGTH + 1)
+            .take_while(|b| is_hex(b))
+            .count();
+        let after = bytes[digit..]
+            .iter()
+            .take(MAX_LENGTH + 1)
+            .take_while(|b| is_hex(b))
+            .count();
+        let start = digit - before;
+        let end = digit + after;
+        if !(7..=MAX_LENGTH).contains(&(end - start)) {
+            return None;
+        }
+        if !bytes[start..end].iter().any(u8::is_ascii_lowercase) {
+            // Only digits, e.g. `1234567`
+            return None;
+        }
+        if !is_start(s, start) || !is_end(s, end) {
+            return None;
+        }
+        Some(start..end)
+    }
+}
+
+impl Scanner for ReferenceScanner {
+    fn scan(&self, s: &str, trigger: usize) -> Option<Range<usize>> {
+        if s.as_bytes()[trigger] == b'#' {
+            return self.scan_issue(s, trigger);
+        }
+        let digit = trigger;
+        if digit > 0 && s.as_bytes()[digit - 1] == b'-' {
+            if let Some(range) = self.scan_key(s, digit) {
+                return Some(range);
+            }
+        }
+        if !self.rules.commits {
+            return None;
+        }
+        self.scan_commit(s, digit)
+    }
+}
+
+fn count_digits(s: &str) -> usize {
+    s.bytes().take_while(u8::is_ascii_digit).count()
+}
+
+/// Whether the digits are a number that fits into the `u64` of a `Reference`.
+fn is_number(digits: &str) -> bool {
+    digits.parse::<u64>().is_ok()
+}
+
+/// Whether a reference can start at the index, which is not the case within words or paths.
+fn is_start(s: &str, index: usize) -> bool {
+    match s[..index].chars().next_back() {
+        Some(c) => !(c.is_alphanumeric() || "_-./&@#".contains(c)),
+        None => true,
+    }
+}
+
+/// Whether a reference can end at the index, which is not the case within words.
+fn is_end(s: &str, index: usize) -> bool {
+    match s[index..].chars().next() {
+        Some(c) => !(c.is_alphanumeric() || "_-@".contains(c)),
+        None => true,
+    }
+}
@@ -0,0 +1,337 @@
+/// Rules for parsing the authority of URLs with a certain scheme.
+///
+/// Use this with [`SchemePolicy::scheme`] to configure how URLs with that scheme are found.
+///
+/// The default rules are lenient, they allow any authority (not only host names), userinfo, ports
+/// and international characters:
+///
+/// ```
+/// use linkify::SchemeRules;
+///
+/// let rules = SchemeRules::new().require_host(true).userinfo_allowed(false);
+/// assert!(rules.host_required());
+/// assert!(!rules.is_userinfo_allowed());
+/// assert!(rules.is_port_allowed());
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SchemeRules {
+    require_host: bool,
+    userinfo_allowed: bool,
+    port_allowed: bool,
+    iri_allowed: bool,
+}
+
+impl SchemeRules {
+    /// Create lenient rules that accept any authority.
+    pub fn new() -> SchemeRules {
+        SchemeRules {
+            require_host: false,
+            userinfo_allowed: true,
+            port_allowed: true,
+            iri_allowed: true,
+        }
+    }
+
+    /// Create rules that require the authority to be a host (domain name or IP address), as used
+    /// for `http` and `https` by default.
+    pub fn host() -> SchemeRules {
+        SchemeRules::new().require_host(true)
+    }
+
+    /// Set whether the authority must look like a host name (domain name or IP address).
+    ///
+    /// If `false`, the authority can be a "reg-name" with arbitrary allowed characters, e.g. in
+    /// `facetime://+19995551234`.
+    pub fn require_host(mut self, value: bool) -> SchemeRules {
+        self.require_host = value;
+        self
+    }
+
+    /// Set whether userinfo (e.g. `user:pass@`) is allowed before the host.
+    pub fn userinfo_allowed(mut self, value: bool) -> SchemeRules {
+        self.userinfo_allowed = value;
+        self
+    }
+
+    /// Set whether a port (e.g. `:8080`) is allowed after the host.
+    pub fn port_allowed(mut self, value: bool) -> SchemeRules {
+        self.port_allowed = value;
+        self
+    }
+
+    /// Set whether the URL can be an IRI (contain non-ASCII characters).
+    ///
+    /// This only has an effect if [`LinkFinder::url_can_be_iri`](crate::LinkFinder::url_can_be_iri)
+    /// is enabled as well.
+    pub fn iri_allowed(mut self, value: bool) -> SchemeRules {
+        self.iri_allowed = value;
+        self
+    }
+
+    /// Whether the authority must be a host.
+    pub fn host_required(&self) -> bool {
+        self.require_host
+    }
+
+    /// Whether userinfo is allowed.
+    pub fn is_userinfo_allowed(&self) -> bool {
+        self.userinfo_allowed
+    }
+
+    /// Whether a port is allowed.
+    pub fn is_port_allowed(&self) -> bool {
+        self.port_allowed
+    }
+
+    /// Whether the URL can be an IRI.
+    pub fn is_iri_allowed(&self) -> bool {
+        self.iri_allowed
+    }
+}
+
+impl Default for SchemeRules {
+    fn default() -> Self {
+        SchemeRules::new()
+    }
+}
+
+/// Syntax of opaque URIs, i.e. URIs without an authority like `mailto:foo@example.org`.
+///
+/// Use this with [`SchemePolicy::opaque`] to find URIs with a certain scheme. Each variant is named
+/// after the scheme it was made for, but can be used for other schemes with the same syntax as
+/// well.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum OpaqueSyntax {
+    /// One or more email addresses separated by `,`, optionally followed by a query, e.g.
+    /// `mailto:foo@example.org?subject=hi` (RFC 6068).
+    Mailto,
+    /// A phone number with optional visual separators and parameters, e.g.
+    /// `tel:+1-555-0100;ext=12` (RFC 3966).
+    Tel,
+    /// A namespace identifier and a namespace specific string, e.g. `urn:isbn:0451450523`
+    /// (RFC 8141).
+    Urn,
+    /// A query only, e.g. `magnet:?xt=urn:btih:...`.
+    Query,
+    /// A Jabber ID with optional resource and query, e.g. `xmpp:user@example.org/phone`
+    /// (RFC 5122).
+    Xmpp,
+    /// A media type followed by `,` and data, e.g. `data:text/plain;base64,SGk=` (RFC 2397).
+    Data,
+    /// Anything that is valid in a URL path, e.g. `news:comp.lang.rust`.
+    Path,
+}
+
+/// Policy for which URL schemes are found and how they are parsed.
+///
+/// By default, URLs with any scheme are found. The schemes `http`, `https`, `ftp` and `ssh` require
+/// a host, all others use the lenient [`SchemeRules::new`].
+///
+/// Schemes are matched case-insensitively.
+///
+/// When deserializing, the rules for schemes are applied over the default rules, so e.g. adding
+/// rules for `gopher` keeps the rules for `http`.
+///
+/// ```
+/// use linkify::{LinkFinder, SchemePolicy, SchemeRules};
+///
+/// let mut policy = SchemePolicy::new();
+/// policy
+///     .scheme("gopher", SchemeRules::host())
+///     .deny(&["javascript"]);
+///
+/// let mut finder = LinkFinder::new();
+/// finder.scheme_policy(policy);
+///
+/// let links: Vec<_> = finder
+///     .links("gopher://example.org/1 javascript://alert(1)")
+///     .collect();
+/// assert_eq!(1, links.len());
+/// assert_eq!("gopher://example.org/1", links[0].as_str());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SchemePolicy {
+    // Lists first, TOML needs values before tables
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    allowed: Option<Vec<String>>,
+    denied: Vec<String>,
+    default_rules: SchemeRules,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "crate::serialization::scheme_map::serialize",
+            deserialize_with = "deserialize_rules"
+        )
+    )]
+    rules: Vec<(String, SchemeRules)>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::scheme_map"))]
+    opaque: Vec<(String, OpaqueSyntax)>,
+}
+
+impl SchemePolicy {
+    /// Create a policy with the default rules, see above.
+    pub fn new() -> SchemePolicy {
+        let mut policy = SchemePolicy {
+            allowed: None,
+            denied: Vec::new(),
+            default_rules: SchemeRules::new(),
+            rules: Vec::new(),
+            opaque: Vec::new(),
+        };
+        for scheme in &["https", "http", "ftp", "ssh"] {
+            policy.scheme(scheme, SchemeRules::host());
+        }
+        policy
+    }
+
+    /// Set the rules for the specified scheme, replacing existing rules for it.
+    pub fn scheme(&mut self, scheme: &str, rules: SchemeRules) -> &mut SchemePolicy {
+        match self
+            .rules
+            .iter_mut()
+            .find(|(s, _)| s.eq_ignore_ascii_case(scheme))
+        {
+            Some(entry) => entry.1 = rules,
+            None => self.rules.push((scheme.to_ascii_lowercase(), rules)),
+        }
+        self
+    }
+
+    /// Find opaque URIs (without `//` after the scheme) with the specified scheme and syntax.
+    ///
+    /// By default, only URLs with an authority such as `https://example.org` are found. Note that
+    /// this takes precedence over finding emails, so e.g. `mailto:foo@example.org` is found as a
+    /// single URL instead of the email `foo@example.org` when `mailto` is registered.
+    ///
+    /// ```
+    /// use linkify::{LinkFinder, OpaqueSyntax, SchemePolicy};
+    ///
+    /// let mut policy = SchemePolicy::new();
+    /// policy.opaque("urn", OpaqueSyntax::Urn);
+    ///
+    /// let mut finder = LinkFinder::new();
+    /// finder.scheme_policy(policy);
+    ///
+    /// let links: Vec<_> = finder.links("See urn:isbn:0451450523.").collect();
+    /// assert_eq!("urn:isbn:0451450523", links[0].as_str());
+    /// ```
+    pub fn opaque(&mut self, scheme: &str, syntax: OpaqueSyntax) -> &mut SchemePolicy {
+        match self
+            .opaque
+            .iter_mut()
+            .find(|(s, _)| s.eq_ignore_ascii_case(scheme))
+        {
+            Some(entry) => entry.1 = syntax,
+            None => self.opaque.push((scheme.to_ascii_lowercase(), syntax)),
+        }
+        self
+    }
+
+    /// Find opaque URIs with commonly used schemes: `mailto`, `tel`, `sms`, `urn`, `magnet`,
+    /// `xmpp` and `data`.
+    pub fn common_opaque_schemes(&mut self) -> &mut SchemePolicy {
+        self.opaque("mailto", OpaqueSyntax::Mailto)
+            .opaque("tel", OpaqueSyntax::Tel)
+            .opaque("sms", OpaqueSyntax::Tel)
+            .opaque("urn", OpaqueSyntax::Urn)
+            .opaque("magnet", OpaqueSyntax::Query)
+            .opaque("xmpp", OpaqueSyntax::Xmpp)
+            .opaque("data", OpaqueSyntax::Data)
+    }
+
+    /// Set the rules for schemes that don't have their own rules.
+    pub fn default_rules(&mut self, rules: SchemeRules) -> &mut SchemePolicy {
+        self.default_rules = rules;
+        self
+    }
+
+    /// Only find URLs with one of the specified schemes (allowlist).
+    ///
+    /// Calling this again adds to the allowed schemes.
+    pub fn allow(&mut self, schemes: &[&str]) -> &mut SchemePolicy {
+        let allowed = self.allowed.get_or_insert_with(Vec::new);
+        allowed.extend(schemes.iter().map(|s| s.to_ascii_lowercase()));
+        self
+    }
+
+    /// Don't find URLs with any of the specified schemes (denylist).
+    ///
+    /// The denylist wins over the allowlist.
+    pub fn deny(&mut self, schemes: &[&str]) -> &mut SchemePolicy {
+        self.denied
+            .extend(schemes.iter().map(|s| s.to_ascii_lowercase()));
+        self
+    }
+
+    /// Whether URLs with the specified scheme should be found.
+    pub fn is_allowed(&self, scheme: &str) -> bool {
+        if contains(&self.denied, scheme) {
+            return false;
+        }
+        match &self.allowed {
+            Some(allowed) => contains(allowed, scheme),
+            None => true,
+        }
+    }
+
+    /// The syntax for opaque URIs with the specified scheme, if registered.
+    pub fn opaque_syntax(&self, scheme: &str) -> Option<OpaqueSyntax> {
+        self.opaque
+            .iter()
+            .find(|(s, _)| s.eq_ignore_ascii_case(scheme))
+            .map(|(_, syntax)| *syntax)
+    }
+
+    pub(crate) fn has_opaque(&self) -> bool {
+        !self.opaque.is_empty()
+    }
+
+    /// The rules for the specified scheme.
+    pub fn rules(&self, scheme: &str) -> SchemeRules {
+        self.rules
+            .iter()
+            .find(|(s, _)| s.eq_ignore_ascii_case(scheme))
+            .map(|(_, rules)| *rules)
+            .unwrap_or(self.default_rules)
+    }
+}
+
+impl Default for SchemePolicy {
+    fn default() -> Self {
+        SchemePolicy::new()
+    }
+}
+
+/// Deserialize the rules of a policy, applying them over the default rules with
+/// [`SchemePolicy::scheme`].
+#[cfg(feature = "serde")]
+fn deserialize_rules<'de, D>(deserializer: D) -> Result<Vec<(String, SchemeRules)>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut policy = SchemePolicy::new();
+    for (scheme, rules) in crate::serialization::scheme_map::deserialize(deserializer)? {
+        policy.scheme(&scheme, rules);
+    }
+    Ok(policy.rules)
+}
+
+fn contains(schemes: &[String], scheme: &str) -> bool {
+    schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme))
+}
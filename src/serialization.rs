@@ -0,0 +1,132 @@
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::finder::{Link, LinkKind, OwnedLink, Span};
+
+// Links are serialized with their text, position, kind and components, e.g.:
+//
+// {"text":"http://example.org","start":4,"end":22,"kind":"url","existing":false,
+//  "defanged":false,"components":{"scheme":{"start":4,"end":8},"host":{"start":11,"end":22}}}
+
+impl<'t> Serialize for Link<'t> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Link", 7)?;
+        state.serialize_field("text", self.as_str())?;
+        state.serialize_field("start", &self.start())?;
+        state.serialize_field("end", &self.end())?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("existing", &self.is_existing())?;
+        state.serialize_field("defanged", &self.is_defanged())?;
+        state.serialize_field("components", &self.components())?;
+        state.end()
+    }
+}
+
+impl Serialize for OwnedLink {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("OwnedLink", 7)?;
+        state.serialize_field("text", self.as_str())?;
+        state.serialize_field("start", &self.start())?;
+        state.serialize_field("end", &self.end())?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("existing", &self.is_existing())?;
+        state.serialize_field("defanged", &self.is_defanged())?;
+        state.serialize_field("components", &self.components())?;
+        state.end()
+    }
+}
+
+/// Spans of plain text have a `kind` and `components` of `null`.
+impl<'t> Serialize for Span<'t> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Span", 7)?;
+        state.serialize_field("text", self.as_str())?;
+        state.serialize_field("start", &self.start())?;
+        state.serialize_field("end", &self.end())?;
+        state.serialize_field("kind", &self.kind())?;
+        state.serialize_field("existing", &self.is_existing())?;
+        state.serialize_field("defanged", &self.is_defanged())?;
+        state.serialize_field("components", &self.link().map(|link| link.components()))?;
+        state.end()
+    }
+}
+
+/// The serialized form of an `OwnedLink`. The components are computed from the text again, so
+/// they are ignored, and `end` must match the length of the text.
+#[derive(serde::Deserialize)]
+#[serde(rename = "OwnedLink")]
+struct OwnedLinkData {
+    text: String,
+    start: usize,
+    end: usize,
+    kind: LinkKind,
+    #[serde(default)]
+    existing: bool,
+    #[serde(default)]
+    defanged: bool,
+}
+
+impl<'de> Deserialize<'de> for OwnedLink {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = OwnedLinkData::deserialize(deserializer)?;
+        if data.start.checked_add(data.text.len()) != Some(data.end) {
+            return Err(de::Error::custom(format_args!(
+                "end {} doesn't match start {} and text length {}",
+                data.end,
+                data.start,
+                data.text.len()
+            )));
+        }
+        Ok(OwnedLink::from_parts(
+            data.text,
+            data.start,
+            data.kind,
+            data.existing,
+            data.defanged,
+        ))
+    }
+}
+
+/// (De)serialize pairs of a scheme and a value (e.g. the rules of a `SchemePolicy`) as a map like
+/// `{"https": {...}}`, keeping the order.
+pub(crate) mod scheme_map {
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+    use serde::ser::{Serialize, Serializer};
+
+    pub(crate) fn serialize<S, T>(pairs: &[(String, T)], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        serializer.collect_map(pairs.iter().map(|(scheme, value)| (scheme, value)))
+    }
+
+    pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<(String, T)>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        deserializer.deserialize_map(PairsVisitor(PhantomData))
+    }
+
+    struct PairsVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for PairsVisitor<T> {
+        type Value = Vec<(String, T)>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a map with schemes as keys")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut pairs = Vec::new();
+            while let Some((scheme, value)) = map.next_entry::<String, T>()? {
+                pairs.push((scheme.to_ascii_lowercase(), value));
+            }
+            Ok(pairs)
+        }
+    }
+}
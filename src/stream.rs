@@ -0,0 +1,251 @@
+//! Finding links in input that is read in chunks.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead};
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+
+#[cfg(feature = "async")]
+use futures_core::Stream;
+#[cfg(feature = "async")]
+use tokio::io::AsyncBufRead;
+
+use crate::finder::{InputFormat, LinkFinder, OwnedLink};
+
+/// The default maximum number of bytes to buffer while looking for whitespace.
+const DEFAULT_MAX_BUFFER: usize = 64 * 1024;
+
+/// Scans input that is pushed in chunks, independent of how it's read.
+///
+/// Only the input up to the last ASCII whitespace is scanned, the rest is kept until more input
+/// is pushed. Links can't contain whitespace, and the backwards scans for the start of a link
+/// (e.g. the scheme or the local part of an email) stop at whitespace too. So scanning the input
+/// in parts split at whitespace finds the same links as scanning it all at once. The exception
+/// are phone numbers, which can contain spaces but not line breaks, so then the input is split
+/// at line breaks instead.
+pub(crate) struct ChunkScanner {
+    finder: LinkFinder,
+    /// Input that has not been scanned yet
+    buffer: Vec<u8>,
+    /// Index of the start of `buffer` within the input
+    offset: usize,
+    /// Whether to only split at line breaks instead of at any whitespace
+    split_at_line_breaks: bool,
+    max_buffer: usize,
+    links: VecDeque<OwnedLink>,
+}
+
+impl ChunkScanner {
+    pub(crate) fn new(finder: &LinkFinder) -> ChunkScanner {
+        // Markup can't be split at whitespace (e.g. tags with attributes), so always scan the
+        // input as plain text
+        let mut finder = finder.clone();
+        finder.input_format(InputFormat::Plain);
+        ChunkScanner {
+            split_at_line_breaks: finder.links_can_contain_spaces(),
+            finder,
+            buffer: Vec::new(),
+            offset: 0,
+            max_buffer: DEFAULT_MAX_BUFFER,
+            links: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn set_max_buffer(&mut self, max_buffer: usize) {
+        self.max_buffer = max_buffer;
+    }
+
+    /// Add the next chunk of input and scan as much of the input as possible.
+    pub(crate) fn push(&mut self, chunk: &[u8]) {
+        let whitespace = if self.split_at_line_breaks {
+            chunk.iter().rposition(|&b| b == b'\n' || b == b'\r')
+        } else {
+            chunk.iter().rposition(u8::is_ascii_whitespace)
+        };
+        self.buffer.extend_from_slice(chunk);
+        match whitespace {
+            Some(i) => {
+                let end = self.buffer.len() - chunk.len() + i + 1;
+                self.scan(end);
+            }
+            None if self.buffer.len() >= self.max_buffer => {
+                // No whitespace for a long time, give up on links across the cut. Don't cut an
+                // incomplete char at the end, so that valid UTF-8 is not split.
+                let len = self.buffer.len();
+                let last_char = (len.saturating_sub(4)..len)
+                    .rev()
+                    .find(|&i| self.buffer[i] & 0xC0 != 0x80);
+                let end = match last_char {
+                    Some(i) if i + utf8_len(self.buffer[i]) > len => i,
+                    _ => len,
+                };
+                self.scan(end);
+            }
+            None => {}
+        }
+    }
+
+    /// Scan the rest of the input, after the last chunk was pushed.
+    pub(crate) fn finish(&mut self) {
+        self.scan(self.buffer.len());
+    }
+
+    /// The next link that was found, if any.
+    pub(crate) fn next_link(&mut self) -> Option<OwnedLink> {
+        self.links.pop_front()
+    }
+
+    fn scan(&mut self, end: usize) {
+        let offset = self.offset;
+        self.links.extend(
+            self.finder
+                .links_bytes(&self.buffer[..end])
+                .map(|link| OwnedLink::from(link).shift(offset)),
+        );
+        self.buffer.drain(..end);
+        self.offset += end;
+    }
+}
+
+/// The length of a UTF-8 encoded char from its first byte.
+fn utf8_len(first: u8) -> usize {
+    match first {
+        0xF0..=0xFF => 4,
+        0xE0..=0xEF => 3,
+        0xC0..=0xDF => 2,
+        _ => 1,
+    }
+}
+
+impl fmt::Debug for ChunkScanner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkScanner")
+            .field("offset", &self.offset)
+            .field("buffered", &self.buffer.len())
+            .finish()
+    }
+}
+
+/// Iterator for finding links in the input of a reader, see [`LinkFinder::links_reader`].
+#[derive(Debug)]
+pub struct ReaderLinks<R> {
+    reader: R,
+    scanner: ChunkScanner,
+    done: bool,
+}
+
+impl<R: BufRead> ReaderLinks<R> {
+    pub(crate) fn new(reader: R, scanner: ChunkScanner) -> ReaderLinks<R> {
+        ReaderLinks {
+            reader,
+            scanner,
+            done: false,
+        }
+    }
+
+    /// Set the maximum number of bytes that are buffered while looking for whitespace, defaults
+    /// to 64 KiB.
+    ///
+    /// If the input contains no whitespace for that long, the buffered input is scanned anyway,
+    /// which means that a link that crosses that point is split.
+    pub fn max_buffer(&mut self, max_buffer: usize) -> &mut ReaderLinks<R> {
+        self.scanner.set_max_buffer(max_buffer);
+        self
+    }
+}
+
+impl<R: BufRead> Iterator for ReaderLinks<R> {
+    type Item = io::Result<OwnedLink>;
+
+    fn next(&mut self) -> Option<io::Result<OwnedLink>> {
+        loop {
+            if let Some(link) = self.scanner.next_link() {
+                return Some(Ok(link));
+            }
+            if self.done {
+                return None;
+            }
+
+            let chunk = match self.reader.fill_buf() {
+                Ok(chunk) => chunk,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+            if chunk.is_empty() {
+                self.done = true;
+                self.scanner.finish();
+            } else {
+                let len = chunk.len();
+                self.scanner.push(chunk);
+                self.reader.consume(len);
+            }
+        }
+    }
+}
+
+/// Stream of links in the input of an async reader, see [`LinkFinder::link_stream`].
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct LinkStream<R> {
+    reader: R,
+    scanner: ChunkScanner,
+    done: bool,
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncBufRead + Unpin> LinkStream<R> {
+    pub(crate) fn new(reader: R, scanner: ChunkScanner) -> LinkStream<R> {
+        LinkStream {
+            reader,
+            scanner,
+            done: false,
+        }
+    }
+
+    /// Set the maximum number of bytes that are buffered while looking for whitespace, see
+    /// [`ReaderLinks::max_buffer`].
+    pub fn max_buffer(&mut self, max_buffer: usize) -> &mut LinkStream<R> {
+        self.scanner.set_max_buffer(max_buffer);
+        self
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncBufRead + Unpin> Stream for LinkStream<R> {
+    type Item = io::Result<OwnedLink>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(link) = this.scanner.next_link() {
+                return Poll::Ready(Some(Ok(link)));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            let chunk = match Pin::new(&mut this.reader).poll_fill_buf(cx) {
+                Poll::Ready(Ok(chunk)) => chunk,
+                Poll::Ready(Err(e)) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+                Poll::Pending => return Poll::Pending,
+            };
+            if chunk.is_empty() {
+                this.done = true;
+                this.scanner.finish();
+            } else {
+                let len = chunk.len();
+                this.scanner.push(chunk);
+                Pin::new(&mut this.reader).consume(len);
+            }
+        }
+    }
+}
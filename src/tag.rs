@@ -0,0 +1,156 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::scanner::Scanner;
+
+/// Rules for the names of mentions (`@alice`) and hashtags (`#release-notes`).
+///
+/// Use this with [`LinkFinder::mention_rules`](crate::LinkFinder::mention_rules) and
+/// [`LinkFinder::hashtag_rules`](crate::LinkFinder::hashtag_rules). Names consist of letters,
+/// digits and the configured extra characters, which are not allowed at the end of a name (so in
+/// `@alice.`, the `.` is not part of the mention).
+///
+/// ```
+/// use linkify::TagRules;
+///
+/// // Twitter-style mentions
+/// let rules = TagRules::mention().chars("_").max_length(15);
+/// assert_eq!("_", rules.extra_chars());
+/// assert_eq!(15, rules.max_len());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TagRules {
+    chars: String,
+    max_length: usize,
+    unicode: bool,
+    require_letter: bool,
+}
+
+impl TagRules {
+    /// Create the default rules for mentions: ASCII letters and digits as well as `_`, `-` and `.`,
+    /// with a maximum length of 39.
+    pub fn mention() -> TagRules {
+        TagRules {
+            chars: "_-.".to_string(),
+            max_length: 39,
+            unicode: false,
+            require_letter: false,
+        }
+    }
+
+    /// Create the default rules for hashtags: letters and digits (including non-ASCII ones) as
+    /// well as `_` and `-`, with a maximum length of 100. Hashtags need to contain at least one
+    /// letter, so `#1` is not a hashtag.
+    pub fn hashtag() -> TagRules {
+        TagRules {
+            chars: "_-".to_string(),
+            max_length: 100,
+            unicode: true,
+            require_letter: true,
+        }
+    }
+
+    /// Set the characters that are allowed in names in addition to letters and digits.
+    pub fn chars(mut self, chars: &str) -> TagRules {
+        self.chars = chars.to_string();
+        self
+    }
+
+    /// Set the maximum length of names in characters (not including `@` or `#`). Longer names are
+    /// not found at all.
+    pub fn max_length(mut self, max_length: usize) -> TagRules {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Set whether non-ASCII letters and digits are allowed in names.
+    pub fn unicode(mut self, unicode: bool) -> TagRules {
+        self.unicode = unicode;
+        self
+    }
+
+    /// Set whether names need to contain at least one letter (not only digits and other
+    /// characters).
+    pub fn require_letter(mut self, require_letter: bool) -> TagRules {
+        self.require_letter = require_letter;
+        self
+    }
+
+    /// The characters that are allowed in names in addition to letters and digits.
+    pub fn extra_chars(&self) -> &str {
+        &self.chars
+    }
+
+    /// The maximum length of names in characters.
+    pub fn max_len(&self) -> usize {
+        self.max_length
+    }
+
+    /// Whether non-ASCII letters and digits are allowed in names.
+    pub fn is_unicode(&self) -> bool {
+        self.unicode
+    }
+
+    /// Whether names need to contain at least one letter.
+    pub fn letter_required(&self) -> bool {
+        self.require_letter
+    }
+
+    fn is_alphanumeric(&self, c: char) -> bool {
+        if self.unicode {
+            c.is_alphanumeric()
+        } else {
+            c.is_ascii_alphanumeric()
+        }
+    }
+}
+
+/// Scan for mentions or hashtags starting from the trigger character, `@` or `#`.
+pub struct TagScanner {
+    pub rules: Arc<TagRules>,
+}
+
+impl Scanner for TagScanner {
+    fn scan(&self, s: &str, sigil: usize) -> Option<Range<usize>> {
+        let rules = &self.rules;
+        if let Some(c) = s[..sigil].chars().next_back() {
+            // Not after a word like in `C#` or `a@b`, an entity like `&#39;`, or a path like
+            // `example.org/@alice`
+            if c.is_alphanumeric() || rules.chars.contains(c) || "_/&@#".contains(c) {
+                return None;
+            }
+        }
+
+        let name_start = sigil + 1;
+        let mut name_end = name_start;
+        let mut trimmed_end = name_start;
+        let mut length = 0;
+        let mut has_letter = false;
+        for (i, c) in s[name_start..].char_indices() {
+            if rules.is_alphanumeric(c) {
+                trimmed_end = name_start + i + c.len_utf8();
+                has_letter |= c.is_alphabetic();
+            } else if !rules.chars.contains(c) {
+                break;
+            }
+            name_end = name_start + i + c.len_utf8();
+            length += 1;
+        }
+
+        if let Some(c) = s[name_end..].chars().next() {
+            // Part of an email or handle like `@alice@example.org`, or another tag
+            if c == '@' || c == '#' || c.is_alphanumeric() {
+                return None;
+            }
+        }
+        let trimmed_length = length - s[trimmed_end..name_end].chars().count();
+        if trimmed_length == 0
+            || trimmed_length > rules.max_length
+            || (rules.require_letter && !has_letter)
+        {
+            return None;
+        }
+        Some(sigil..trimmed_end)
+    }
+}
@@ -0,0 +1,150 @@
+//! Rendering of text with links as terminal hyperlinks.
+//!
+//! The [`TerminalRenderer`] wraps links in [OSC 8] escape sequences, which terminals that support
+//! them display as clickable links. Text that isn't a link is written unchanged. Use it with
+//! [`InputFormat::Ansi`](crate::InputFormat::Ansi) for text that already contains escape
+//! sequences such as colors:
+//!
+//! ```
+//! use linkify::terminal::TerminalRenderer;
+//! use linkify::{InputFormat, LinkFinder, LinkKind};
+//!
+//! let mut finder = LinkFinder::new();
+//! finder.input_format(InputFormat::Ansi);
+//!
+//! let mut renderer = TerminalRenderer::new();
+//! renderer.style(LinkKind::Url, "4", "24");
+//!
+//! let output = renderer.render(finder.spans("\x1b[31mError\x1b[0m: see http://example.org"));
+//! assert_eq!(
+//!     output,
+//!     "\x1b[31mError\x1b[0m: see \
+//!      \x1b]8;;http://example.org\x1b\\\x1b[4mhttp://example.org\x1b[24m\x1b]8;;\x1b\\"
+//! );
+//! ```
+//!
+//! [OSC 8]: https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda
+
+use std::fmt;
+
+use crate::ansi;
+use crate::finder::{Link, LinkKind, Spans};
+use crate::html;
+
+/// Renders spans with links as terminal hyperlinks, see the [module docs](self).
+pub struct TerminalRenderer {
+    default_scheme: String,
+    styles: Vec<(LinkKind, String, String)>,
+}
+
+impl TerminalRenderer {
+    /// Create a new renderer that renders links as hyperlinks without any styling.
+    pub fn new() -> TerminalRenderer {
+        TerminalRenderer {
+            default_scheme: "https".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    /// Set the scheme to use in the URI of URLs without a scheme such as `example.org`, defaults
+    /// to `https`.
+    pub fn default_scheme(&mut self, scheme: &str) -> &mut TerminalRenderer {
+        self.default_scheme = scheme.to_string();
+        self
+    }
+
+    /// Style links of the specified kind with SGR (Select Graphic Rendition) parameters, e.g.
+    /// `"4"` to turn on underlining and `"24"` to turn it off again after the link, or `"1;34"`
+    /// and `"22;39"` for bold and blue.
+    ///
+    /// Styles apply to all links of the kind, including mentions and hashtags that aren't
+    /// rendered as hyperlinks. Setting a style again for the same kind replaces the previous one.
+    pub fn style(&mut self, kind: LinkKind, on: &str, off: &str) -> &mut TerminalRenderer {
+        match self.styles.iter_mut().find(|(k, _, _)| *k == kind) {
+            Some(style) => {
+                style.1 = on.to_string();
+                style.2 = off.to_string();
+            }
+            None => self.styles.push((kind, on.to_string(), off.to_string())),
+        }
+        self
+    }
+
+    /// Render the spans with hyperlinks.
+    pub fn render(&self, spans: Spans<'_>) -> String {
+        let mut output = String::new();
+        self.render_to(spans, &mut output)
+            .expect("writing to a String doesn't fail");
+        output
+    }
+
+    /// Render the spans with hyperlinks to the specified writer.
+    pub fn render_to<W: fmt::Write>(&self, spans: Spans<'_>, out: &mut W) -> fmt::Result {
+        for span in spans {
+            match span.link() {
+                Some(link) => self.render_link(&link, out)?,
+                None => out.write_str(span.as_str())?,
+            }
+        }
+        Ok(())
+    }
+
+    fn render_link<W: fmt::Write>(&self, link: &Link<'_>, out: &mut W) -> fmt::Result {
+        let uri = match uri(link, &self.default_scheme) {
+            Some(uri) => uri,
+            None => return self.render_styled(link, out),
+        };
+        write!(out, "\x1b]8;;{}\x1b\\", uri)?;
+        self.render_styled(link, out)?;
+        out.write_str("\x1b]8;;\x1b\\")
+    }
+
+    fn render_styled<W: fmt::Write>(&self, link: &Link<'_>, out: &mut W) -> fmt::Result {
+        match self.styles.iter().find(|(kind, _, _)| kind == link.kind()) {
+            Some((_, on, off)) => write!(out, "\x1b[{}m{}\x1b[{}m", on, link.as_str(), off),
+            None => out.write_str(link.as_str()),
+        }
+    }
+}
+
+impl Default for TerminalRenderer {
+    fn default() -> Self {
+        TerminalRenderer::new()
+    }
+}
+
+impl fmt::Debug for TerminalRenderer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TerminalRenderer")
+            .field("default_scheme", &self.default_scheme)
+            .field("styles", &self.styles)
+            .finish()
+    }
+}
+
+/// The URI for the hyperlink of the link, without escape sequences and with bytes that are not
+/// allowed in OSC 8 percent-encoded. Mentions, hashtags, references without a URL template and
+/// defanged links don't have one.
+fn uri(link: &Link<'_>, default_scheme: &str) -> Option<String> {
+    match link.kind() {
+        LinkKind::Mention | LinkKind::Hashtag => return None,
+        LinkKind::Reference if link.reference_url().is_none() => return None,
+        _ => {}
+    }
+    if link.is_defanged() {
+        return None;
+    }
+    let href = ansi::strip(&html::href(link, default_scheme));
+    let mut uri = String::with_capacity(href.len());
+    for c in href.chars() {
+        if ('!'..='~').contains(&c) {
+            uri.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for b in c.encode_utf8(&mut buf).bytes() {
+                uri.push_str(&format!("%{:02X}", b));
+            }
+        }
+    }
+    Some(uri)
+}
@@ -1,8 +1,11 @@
 use std::char;
 use std::ops::Range;
+use std::sync::Arc;
 
-use crate::domains::find_authority_end;
+use crate::domains::{find_authority_end, TldValidator};
+use crate::opaque::find_opaque_end;
 use crate::scanner::Scanner;
+use crate::scheme::SchemePolicy;
 
 /// Minimum valid URL length
 ///
@@ -15,16 +18,20 @@ const MIN_URL_LENGTH: usize = 4;
 
 const QUOTES: &[char] = &['\'', '\"'];
 
-/// Scan for URLs starting from the trigger character ":" (requires "://").
+/// Scan for URLs starting from the trigger character ":".
+///
+/// Requires "://" unless the scheme was registered as opaque in the scheme policy.
 ///
 /// Based on RFC 3986.
 pub struct UrlScanner {
     pub iri_parsing_enabled: bool,
+    pub scheme_policy: Arc<SchemePolicy>,
 }
 
 /// Scan for plain domains (without scheme) such as `test.com` or `test.com/hi-there`.
 pub struct DomainScanner {
     pub iri_parsing_enabled: bool,
+    pub tld_validator: Option<TldValidator>,
 }
 
 impl Scanner for UrlScanner {
@@ -37,11 +44,19 @@ impl Scanner for UrlScanner {
             return None;
         }
 
-        if !s[separator..].starts_with("://") {
-            // We only support schemes with authority, not things like `myscheme:mything`.
-            return None;
+        if s[separator..].starts_with("://") {
+            self.scan_with_authority(s, separator)
+        } else if self.scheme_policy.has_opaque() {
+            // Things like `mailto:foo@example.org`, but only for schemes that were registered.
+            self.scan_opaque(s, separator)
+        } else {
+            None
         }
+    }
+}
 
+impl UrlScanner {
+    fn scan_with_authority(&self, s: &str, separator: usize) -> Option<Range<usize>> {
         let after_separator = separator + "://".len();
 
         // Need at least one character after '//'
@@ -51,16 +66,23 @@ impl Scanner for UrlScanner {
 
         if let (Some(start), quote) = find_scheme_start(&s[0..separator]) {
             let scheme = &s[start..separator];
+            if !self.scheme_policy.is_allowed(scheme) {
+                return None;
+            }
+
             let s = &s[after_separator..];
 
-            let require_host = scheme_requires_host(scheme);
+            let rules = self.scheme_policy.rules(scheme);
+            let iri_parsing_enabled = self.iri_parsing_enabled && rules.is_iri_allowed();
 
-            if let (Some(after_authority), _) =
-                find_authority_end(s, true, require_host, true, self.iri_parsing_enabled)
-            {
-                if let Some(end) =
-                    find_url_end(&s[after_authority..], quote, self.iri_parsing_enabled)
-                {
+            if let (Some(after_authority), _) = find_authority_end(
+                s,
+                rules.is_userinfo_allowed(),
+                rules.host_required(),
+                rules.is_port_allowed(),
+                iri_parsing_enabled,
+            ) {
+                if let Some(end) = find_url_end(&s[after_authority..], quote, iri_parsing_enabled) {
                     if after_authority == 0 && end == 0 {
                         return None;
                     }
@@ -76,6 +98,33 @@ impl Scanner for UrlScanner {
 
         None
     }
+
+    fn scan_opaque(&self, s: &str, separator: usize) -> Option<Range<usize>> {
+        let after_separator = separator + ":".len();
+
+        if let (Some(start), quote) = find_scheme_start(&s[0..separator]) {
+            let scheme = &s[start..separator];
+            let syntax = self.scheme_policy.opaque_syntax(scheme)?;
+            if !self.scheme_policy.is_allowed(scheme) {
+                return None;
+            }
+
+            let iri_parsing_enabled =
+                self.iri_parsing_enabled && self.scheme_policy.rules(scheme).is_iri_allowed();
+
+            if let Some(end) =
+                find_opaque_end(&s[after_separator..], syntax, quote, iri_parsing_enabled)
+            {
+                let range = Range {
+                    start,
+                    end: after_separator + end,
+                };
+                return Some(range);
+            }
+        }
+
+        None
+    }
 }
 
 impl Scanner for DomainScanner {
@@ -89,9 +138,14 @@ impl Scanner for DomainScanner {
         {
             let s = &s[start..];
 
-            if let (Some(domain_end), Some(_)) =
+            if let (Some(domain_end), Some(last_dot)) =
                 find_authority_end(s, false, true, true, self.iri_parsing_enabled)
             {
+                if let Some(validator) = &self.tld_validator {
+                    if !validator.accepts(&s[..domain_end], Some(last_dot)) {
+                        return None;
+                    }
+                }
                 if let Some(end) = find_url_end(&s[domain_end..], quote, self.iri_parsing_enabled) {
                     let range = Range {
                         start,
@@ -141,14 +195,6 @@ fn find_scheme_start(s: &str) -> (Option<usize>, Option<char>) {
     (first, quote)
 }
 
-/// Whether a scheme requires that authority looks like a host name (domain or IP address) or not
-/// (can contain reg-name with arbitrary allowed characters).
-///
-/// We could make this configurable, but let's keep it simple until someone asks (hi!).
-fn scheme_requires_host(scheme: &str) -> bool {
-    matches!(scheme, "https" | "http" | "ftp" | "ssh")
-}
-
 /// Find the start of a plain domain URL (no scheme), e.g. from `blog.`, start at `g` and end at `b`.
 /// The rules are:
 /// - Domain is labels separated by `.`. Because we're starting at the first `.`, we only need to
@@ -201,7 +247,25 @@ fn find_domain_start(s: &str, iri_parsing_enabled: bool) -> (Option<usize>, Opti
 
 /// Find the end of a URL. At this point we already scanned past a valid authority. So e.g. in
 /// `https://example.com/foo` we're starting at `/` and want to end at `o`.
-fn find_url_end(s: &str, quote: Option<char>, iri_parsing_enabled: bool) -> Option<usize> {
+pub(crate) fn find_url_end(
+    s: &str,
+    quote: Option<char>,
+    iri_parsing_enabled: bool,
+) -> Option<usize> {
+    if !s[0..].starts_with("/") && !s[0..].starts_with("?") {
+        return Some(0);
+    }
+
+    find_path_end(s, quote, iri_parsing_enabled)
+}
+
+/// Find the end of the characters that can be part of a URL, taking care of trailing punctuation
+/// and balanced brackets. Unlike `find_url_end`, this doesn't require a path or query to start.
+pub(crate) fn find_path_end(
+    s: &str,
+    quote: Option<char>,
+    iri_parsing_enabled: bool,
+) -> Option<usize> {
     let mut round = 0;
     let mut square = 0;
     let mut curly = 0;
@@ -210,10 +274,6 @@ fn find_url_end(s: &str, quote: Option<char>, iri_parsing_enabled: bool) -> Opti
     let mut previous_is_url_char = true;
     let mut end = Some(0);
 
-    if !s[0..].starts_with("/") && !s[0..].starts_with("?") {
-        return Some(0);
-    }
-
     for (i, c) in s.char_indices() {
         let can_be_last = match c {
             '\u{00}'..='\u{1F}' | ' ' | '|' | '\"' | '<' | '>' | '`' | '\u{7F}'..='\u{9F}' => {
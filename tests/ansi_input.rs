@@ -0,0 +1,120 @@
+mod common;
+
+use crate::common::assert_linked_with;
+use linkify::{InputFormat, LinkFinder, LinkKind};
+
+#[test]
+fn plain_text() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "See http://example.org/ or foo@example.org",
+        "See |http://example.org/| or |foo@example.org|",
+    );
+}
+
+#[test]
+fn escapes_around_links() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "\x1b[32mhttp://example.org\x1b[0m",
+        "\x1b[32m|http://example.org|\x1b[0m",
+    );
+    assert_linked_with(
+        &finder,
+        "\x1b[1;34mfoo@example.org\x1b[22;39m, \x1b[4mhttp://example.com/\x1b[24m.",
+        "\x1b[1;34m|foo@example.org|\x1b[22;39m, \x1b[4m|http://example.com/|\x1b[24m.",
+    );
+    // Without the escapes being skipped, `31mhttp` would be the scheme
+    assert_linked_with(
+        &finder,
+        "Error:\x1b[31mhttp://example.org",
+        "Error:\x1b[31m|http://example.org|",
+    );
+}
+
+#[test]
+fn escapes_in_links() {
+    let finder = finder();
+    // Links are found in the text as it's displayed
+    assert_linked_with(
+        &finder,
+        "http://example.org/\x1b[1mpath\x1b[0m/file",
+        "|http://example.org/\x1b[1mpath\x1b[0m/file|",
+    );
+    assert_linked_with(
+        &finder,
+        "foo@\x1b[33mexample.org\x1b[0m",
+        "|foo@\x1b[33mexample.org|\x1b[0m",
+    );
+}
+
+#[test]
+fn other_escapes() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "\x1b]0;window title http://example.org\x07\x1b(Bhttp://example.com\x1b[K",
+        "\x1b]0;window title http://example.org\x07\x1b(B|http://example.com|\x1b[K",
+    );
+    // Incomplete escapes only skip the escape character, except for OSC which continues until
+    // the end of the input without a terminator (like in terminals)
+    assert_linked_with(
+        &finder,
+        "\x1b[31\nhttp://example.org \x1b]8;;http://example.com",
+        "\x1b[31\n|http://example.org| \x1b]8;;http://example.com",
+    );
+}
+
+#[test]
+fn many_unterminated_osc() {
+    // Each of these used to scan to the end of the input
+    let finder = finder();
+    let text = format!("http://example.org {}", "\x1b]8;;".repeat(50_000));
+    let links: Vec<_> = finder.links(&text).map(|link| link.as_str()).collect();
+    assert_eq!(links, vec!["http://example.org"]);
+}
+
+#[test]
+fn existing_hyperlinks() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "\x1b]8;;http://example.org\x1b\\http://example.org\x1b]8;;\x1b\\ http://example.com",
+        "\x1b]8;;http://example.org\x1b\\http://example.org\x1b]8;;\x1b\\ |http://example.com|",
+    );
+    assert_linked_with(
+        &finder,
+        "\x1b]8;id=1;http://example.org\x07see \x1b[4mexample.org\x1b[0m\x1b]8;;\x07 http://example.com",
+        "\x1b]8;id=1;http://example.org\x07see \x1b[4mexample.org\x1b[0m\x1b]8;;\x07 |http://example.com|",
+    );
+}
+
+#[test]
+fn link_indexes() {
+    let finder = finder();
+    let input = "\x1b[31mhttp://example.org/\x1b[1mpath\x1b[0m!";
+    let link = finder.links(input).next().unwrap();
+    assert_eq!(link.start(), 5);
+    assert_eq!(link.end(), input.len() - 5);
+    assert_eq!(link.as_str(), "http://example.org/\x1b[1mpath");
+}
+
+#[test]
+fn defanged() {
+    let mut finder = finder();
+    finder.defanged(true);
+    assert_linked_with(
+        &finder,
+        "\x1b[31mhxxp://example[.]org\x1b[0m",
+        "\x1b[31m|hxxp://example[.]org|\x1b[0m",
+    );
+}
+
+fn finder() -> LinkFinder {
+    let mut finder = LinkFinder::new();
+    finder.input_format(InputFormat::Ansi);
+    finder.kinds(&[LinkKind::Url, LinkKind::Email]);
+    finder
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "async")]
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_util::task::noop_waker;
+use futures_util::StreamExt;
+use linkify::{LinkFinder, OwnedLink};
+use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf};
+
+#[test]
+fn stream_links() {
+    let input = "See http://example.org/a and foo@example.org\nor https://example.com";
+    let finder = LinkFinder::new();
+    let expected: Vec<_> = finder.links(input).map(OwnedLink::from).collect();
+
+    let links = block_on(finder.link_stream(input.as_bytes()).collect::<Vec<_>>());
+    let links: Vec<_> = links.into_iter().map(|link| link.unwrap()).collect();
+    assert_eq!(links, expected);
+
+    for chunk_size in 1..input.len() {
+        let reader = Chunked::new(input.as_bytes(), chunk_size);
+        let links = block_on(finder.link_stream(reader).collect::<Vec<_>>());
+        let links: Vec<_> = links.into_iter().map(|link| link.unwrap()).collect();
+        assert_eq!(links, expected, "chunk size {}", chunk_size);
+    }
+}
+
+#[test]
+fn read_error() {
+    let mut reader = Chunked::new(b"http://example.org http://example.com", 20);
+    reader.fail = true;
+    let finder = LinkFinder::new();
+    let links = block_on(finder.link_stream(reader).collect::<Vec<_>>());
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[0].as_ref().unwrap().as_str(), "http://example.org");
+    assert!(links[1].is_err());
+}
+
+/// Reader that returns chunks of the specified size, and is pending before each chunk.
+struct Chunked<'a> {
+    data: &'a [u8],
+    chunk_size: usize,
+    pending: bool,
+    fail: bool,
+}
+
+impl<'a> Chunked<'a> {
+    fn new(data: &'a [u8], chunk_size: usize) -> Chunked<'a> {
+        Chunked {
+            data,
+            chunk_size,
+            pending: true,
+            fail: false,
+        }
+    }
+}
+
+impl<'a> AsyncRead for Chunked<'a> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        unimplemented!()
+    }
+}
+
+impl<'a> AsyncBufRead for Chunked<'a> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.pending {
+            this.pending = false;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        if this.data.is_empty() && this.fail {
+            return Poll::Ready(Err(io::ErrorKind::ConnectionReset.into()));
+        }
+        let len = this.chunk_size.min(this.data.len());
+        Poll::Ready(Ok(&this.data[..len]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.data = &this.data[amt..];
+        this.pending = true;
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
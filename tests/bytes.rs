@@ -0,0 +1,50 @@
+use linkify::{LinkFinder, LinkKind};
+
+#[test]
+fn valid_utf8() {
+    let input = "See http://example.org/ä and foo@example.org";
+    let links = links(input.as_bytes());
+    assert_eq!(
+        links,
+        vec![("http://example.org/ä", 4, 25), ("foo@example.org", 30, 45),]
+    );
+}
+
+#[test]
+fn invalid_sequences_end_links() {
+    let input = b"http://example.org/a\xffb http://\xfeexample.org";
+    assert_eq!(links(input), vec![("http://example.org/a", 0, 20)]);
+
+    let input = b"foo\xc3@example.org \xe2\x82http://example.com";
+    assert_eq!(links(input), vec![("http://example.com", 19, 37)]);
+}
+
+#[test]
+fn invalid_sequence_at_end() {
+    // Incomplete sequence at the end
+    let input = b"http://example.org\xe2\x82";
+    assert_eq!(links(input), vec![("http://example.org", 0, 18)]);
+    assert_eq!(links(b"\xff"), vec![]);
+    assert_eq!(links(b""), vec![]);
+}
+
+#[test]
+fn components_and_kind() {
+    let input = b"\xff\xffhttps://example.org:8080/path";
+    let finder = LinkFinder::new();
+    let link = finder.links_bytes(input).next().unwrap();
+    assert_eq!(link.kind(), &LinkKind::Url);
+    let components = link.components();
+    assert_eq!(components.host(), Some(10..21));
+    assert_eq!(components.port(), Some(22..26));
+    assert_eq!(&input[components.path().unwrap()], b"/path");
+    assert_eq!(link.host_kind(), Some(linkify::HostKind::Domain));
+}
+
+fn links(input: &[u8]) -> Vec<(&str, usize, usize)> {
+    let finder = LinkFinder::new();
+    finder
+        .links_bytes(input)
+        .map(|link| (link.as_str(), link.start(), link.end()))
+        .collect()
+}
@@ -0,0 +1,276 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+const INPUT: &str = "\
+See https://example.org/a and
+mail foo@example.org or example.com (https://example.org/a)
+";
+
+#[test]
+fn plain() {
+    assert_eq!(
+        run(&[], INPUT),
+        "https://example.org/a\nfoo@example.org\nhttps://example.org/a\n"
+    );
+}
+
+#[test]
+fn unique_and_sorted() {
+    assert_eq!(
+        run(&["-u", "--sort", "--url-without-scheme"], INPUT),
+        "example.com\nfoo@example.org\nhttps://example.org/a\n"
+    );
+}
+
+#[test]
+fn plain_streams_input() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_linkify"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    // The first link is printed before the input ends
+    stdin.write_all(b"See https://example.org/a\n").unwrap();
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    assert_eq!(line, "https://example.org/a\n");
+
+    stdin.write_all(b"and https://example.org/b\n").unwrap();
+    drop(stdin);
+    line.clear();
+    stdout.read_line(&mut line).unwrap();
+    assert_eq!(line, "https://example.org/b\n");
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn kinds() {
+    assert_eq!(run(&["--kinds", "email"], INPUT), "foo@example.org\n");
+    assert_eq!(
+        run(&["-k", "mention,hashtag"], "@alice likes #rust"),
+        "@alice\n#rust\n"
+    );
+}
+
+#[test]
+fn grep() {
+    assert_eq!(
+        run(&["-o", "grep", "-k", "url"], INPUT),
+        "<stdin>:1:5:https://example.org/a\n<stdin>:2:38:https://example.org/a\n"
+    );
+}
+
+#[test]
+fn invalid_utf8() {
+    let input = b"\xff\xfe a http://a.org\n\xc3 foo@example.org";
+    let output = command(&["-o", "grep"], &input[..]).unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "<stdin>:1:6:http://a.org\n<stdin>:2:3:foo@example.org\n"
+    );
+
+    let output = command(&["-o", "json"], &input[..]).unwrap();
+    let output = String::from_utf8(output.stdout).unwrap();
+    let values: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(values[0]["start"], 5);
+    assert_eq!(values[0]["end"], 17);
+    assert_eq!(values[1]["start"], 20);
+    assert_eq!(values[1]["end"], 35);
+}
+
+#[test]
+fn json() {
+    let output = run(&["--output", "json", "-k", "email"], INPUT);
+    let value: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+    assert_eq!(value["file"], "<stdin>");
+    assert_eq!(value["line"], 2);
+    assert_eq!(value["column"], 6);
+    assert_eq!(value["text"], "foo@example.org");
+    assert_eq!(value["start"], 35);
+    assert_eq!(value["kind"], "email");
+    assert_eq!(value["components"]["host"]["start"], 39);
+}
+
+#[test]
+fn html() {
+    assert_eq!(
+        run(&["-o", "html"], "<b> http://example.org"),
+        "&lt;b&gt; <a href=\"http://example.org\">http://example.org</a>"
+    );
+}
+
+#[test]
+fn markdown() {
+    assert_eq!(
+        run(
+            &["-o", "markdown", "--url-without-scheme"],
+            "See example.org/a_b, https://example.org or foo@example.org"
+        ),
+        "See [example.org/a\\_b](https://example.org/a_b), <https://example.org> or <foo@example.org>"
+    );
+    assert_eq!(
+        run(
+            &["-o", "markdown", "--input-format", "markdown"],
+            "`http://code.org` [link](http://a.org) http://b.org"
+        ),
+        "`http://code.org` [link](http://a.org) <http://b.org>"
+    );
+}
+
+#[test]
+fn terminal() {
+    assert_eq!(
+        run(
+            &["-o", "terminal", "--input-format", "ansi"],
+            "\x1b[31merror\x1b[0m: see \x1b[4mhttp://example.org\x1b[24m"
+        ),
+        "\x1b[31merror\x1b[0m: see \x1b[4m\x1b]8;;http://example.org\x1b\\http://example.org\x1b]8;;\x1b\\\x1b[24m"
+    );
+}
+
+#[test]
+fn references() {
+    assert_eq!(
+        run(
+            &[
+                "-o",
+                "markdown",
+                "-k",
+                "reference",
+                "--repository",
+                "robinst/linkify",
+                "--issue-url",
+                "https://github.com/{owner}/{repo}/issues/{n}",
+            ],
+            "Fixes #42"
+        ),
+        "Fixes [#42](https://github.com/robinst/linkify/issues/42)"
+    );
+}
+
+#[test]
+fn defanged() {
+    let input = "Blocked hxxps://evil[.]com/x";
+    assert_eq!(run(&["--defanged"], input), "https://evil.com/x\n");
+    assert_eq!(run(&["--defanged", "-o", "markdown"], input), input);
+}
+
+#[test]
+fn finder_options() {
+    assert_eq!(
+        run(&["--email-without-dot"], "root@localhost"),
+        "root@localhost\n"
+    );
+    assert_eq!(run(&["--no-iri"], "http://ex.org/ü"), "http://ex.org/\n");
+    assert_eq!(
+        run(
+            &["--url-without-scheme", "--known-tlds"],
+            "notes.txt example.com"
+        ),
+        "example.com\n"
+    );
+    assert_eq!(
+        run(&["--deny-schemes", "ftp"], "ftp://a.org http://b.org"),
+        "http://b.org\n"
+    );
+    assert_eq!(
+        run(&["--opaque-schemes"], "mailto:foo@example.org"),
+        "mailto:foo@example.org\n"
+    );
+    assert_eq!(
+        run(
+            &["--input-format", "html", "--existing-links"],
+            "<a href=\"http://a.org\">a</a> http://b.org"
+        ),
+        "http://a.org\nhttp://b.org\n"
+    );
+}
+
+#[test]
+fn config_file() {
+    let path = std::env::temp_dir().join("linkify-cli-test.toml");
+    fs::write(&path, "kinds = [\"url\"]\nurl_must_have_scheme = false\n").unwrap();
+    let config = path.to_str().unwrap();
+    assert_eq!(
+        run(&["-c", config], INPUT.lines().nth(1).unwrap()),
+        "example.com\nhttps://example.org/a\n"
+    );
+    // Flags override the config
+    assert_eq!(
+        run(&["-c", config, "-k", "email"], INPUT),
+        "foo@example.org\n"
+    );
+
+    fs::write(&path, "[scheme_policy]\ndenied = [\"ftp\"]\n").unwrap();
+    assert_eq!(
+        run(
+            &["-c", config, "--deny-schemes", "http"],
+            "http://a.org ftp://b.org https://c.org"
+        ),
+        "https://c.org\n"
+    );
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn files() {
+    let path = std::env::temp_dir().join("linkify-cli-test.txt");
+    fs::write(&path, "a\nb http://example.org\n").unwrap();
+    let file = path.to_str().unwrap();
+    assert_eq!(
+        run(&["-o", "grep", file, "-"], "http://example.com"),
+        format!(
+            "{}:2:3:http://example.org\n<stdin>:1:1:http://example.com\n",
+            file
+        )
+    );
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn errors() {
+    for args in &[
+        &["--kinds", "foo"][..],
+        &["--output", "pdf"],
+        &["--bogus"],
+        &["-o", "html", "--input-format", "markdown"],
+        &["-o", "markdown", "--sort"],
+        &["-o", "terminal", "--input-format", "html"],
+        &["does-not-exist.txt"],
+    ] {
+        let output = command(args, "").unwrap();
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .starts_with("linkify: "));
+    }
+}
+
+fn run(args: &[&str], input: &str) -> String {
+    let output = command(args, input).unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn command<I: AsRef<[u8]> + ?Sized>(
+    args: &[&str],
+    input: &I,
+) -> std::io::Result<std::process::Output> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_linkify"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().unwrap().write_all(input.as_ref())?;
+    child.wait_with_output()
+}
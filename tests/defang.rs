@@ -0,0 +1,192 @@
+mod common;
+
+use crate::common::assert_linked_with;
+use linkify::{HostKind, InputFormat, LinkFinder, LinkKind};
+
+#[test]
+fn not_found_by_default() {
+    let finder = LinkFinder::new();
+    // Brackets are not allowed in domains
+    assert_linked_with(
+        &finder,
+        "hxxps://evil[.]example[.]com",
+        "|hxxps://evil|[.]example[.]com",
+    );
+}
+
+#[test]
+fn defanged_urls() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "Seen hxxps://evil[.]example[.]com/path today",
+        "Seen |hxxps://evil[.]example[.]com/path| today",
+    );
+    assert_linked_with(&finder, "hXXp://evil(.)com", "|hXXp://evil(.)com|");
+    assert_linked_with(
+        &finder,
+        "hxxps[://]evil{dot}com",
+        "|hxxps[://]evil{dot}com|",
+    );
+    assert_linked_with(&finder, "http[:]//evil[.]com", "|http[:]//evil[.]com|");
+    assert_linked_with(
+        &finder,
+        "fxp://files[.]example[.]org",
+        "|fxp://files[.]example[.]org|",
+    );
+    // Not a scheme in the middle of a word
+    let link = finder.links("ahxxp://evil[.]com").next().unwrap();
+    assert_eq!(link.refanged(), "ahxxp://evil.com");
+}
+
+#[test]
+fn defanged_emails() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "Mail user[at]example[dot]com or admin[@]example[.]org",
+        "Mail |user[at]example[dot]com| or |admin[@]example[.]org|",
+    );
+}
+
+#[test]
+fn defanged_domains_without_scheme() {
+    let mut finder = finder();
+    finder.url_must_have_scheme(false);
+    assert_linked_with(&finder, "evil[.]example[.]com", "|evil[.]example[.]com|");
+}
+
+#[test]
+fn normal_links_still_found() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "https://example.org and a [.] b",
+        "|https://example.org| and a [.] b",
+    );
+}
+
+#[test]
+fn refanged() {
+    let finder = finder();
+    let links: Vec<_> = finder
+        .links("hxxps://evil[.]example[.]com/x user[at]example[dot]com https://example.org")
+        .collect();
+    assert_eq!(links[0].refanged(), "https://evil.example.com/x");
+    assert_eq!(links[0].start(), 0);
+    assert_eq!(links[0].end(), 30);
+    assert_eq!(*links[1].kind(), LinkKind::Email);
+    assert_eq!(links[1].refanged(), "user@example.com");
+    assert_eq!(links[2].refanged(), "https://example.org");
+}
+
+#[test]
+fn only_scheme_and_host_refanged() {
+    let finder = finder();
+    let links: Vec<_> = finder
+        .links("hxxps://example[.]com/a[.]b https://example.com/x(at)y a(dot)b@example[.]com")
+        .collect();
+    assert!(links[0].is_defanged());
+    assert_eq!(links[0].refanged(), "https://example.com/a[.]b");
+    assert!(!links[1].is_defanged());
+    assert_eq!(links[1].refanged(), "https://example.com/x(at)y");
+    assert_eq!(links[1].components().path(), Some(47..54));
+    assert!(links[2].is_defanged());
+    assert_eq!(links[2].refanged(), "a(dot)b@example.com");
+
+    let links: Vec<_> = LinkFinder::new().links("hxxps://example[.]com").collect();
+    assert!(!links[0].is_defanged());
+    assert_eq!(links[0].refanged(), "hxxps://example");
+}
+
+#[test]
+fn html_input() {
+    let mut finder = finder();
+    finder.input_format(InputFormat::Html);
+    assert_linked_with(
+        &finder,
+        "<p>hxxp://evil[.]com/?a=1&amp;b=2</p>",
+        "<p>|hxxp://evil[.]com/?a=1&amp;b=2|</p>",
+    );
+}
+
+#[test]
+fn defang() {
+    let finder = LinkFinder::new();
+    assert_eq!(
+        finder.defang("See https://evil.example.com/a.html, http://x.org and a@b.example.com."),
+        "See hxxps://evil[.]example[.]com/a.html, hxxp://x[.]org and a[at]b[.]example[.]com."
+    );
+    assert_eq!(
+        finder.defang("ftp://files.example.org mailto:a@example.org"),
+        "fxp://files[.]example[.]org mailto:a[at]example[.]org"
+    );
+    assert_eq!(finder.defang("No links here."), "No links here.");
+}
+
+#[test]
+fn defang_keeps_paths() {
+    assert_eq!(
+        LinkFinder::new().defang(
+            "https://en.wikipedia.org/wiki/Foo_(dot) https://example.com/a[.]b https://example.com/x(at)y"
+        ),
+        "hxxps://en[.]wikipedia[.]org/wiki/Foo_(dot) hxxps://example[.]com/a[.]b hxxps://example[.]com/x(at)y"
+    );
+}
+
+#[test]
+fn defang_defanged() {
+    let finder = finder();
+    assert_eq!(
+        finder.defang("hXXp://evil(dot)com user[at]example[dot]com"),
+        "hxxp://evil[.]com user[at]example[.]com"
+    );
+}
+
+fn finder() -> LinkFinder {
+    let mut finder = LinkFinder::new();
+    finder.defanged(true);
+    finder
+}
+
+#[test]
+fn components_of_defanged_links() {
+    let mut finder = LinkFinder::new();
+    finder.defanged(true);
+
+    let input = "hxxps[://]evil[.]example[.]com/x";
+    let link = finder.links(input).next().unwrap();
+    let components = link.components();
+    assert_eq!(components.scheme().map(|r| &input[r]), Some("hxxps"));
+    assert_eq!(
+        components.host().map(|r| &input[r]),
+        Some("evil[.]example[.]com")
+    );
+    assert_eq!(components.path().map(|r| &input[r]), Some("/x"));
+    assert_eq!(link.host_kind(), Some(HostKind::Domain));
+
+    let link = finder.links("hxxp://1.2.3[.]4/a").next().unwrap();
+    assert_eq!(link.host_kind(), Some(HostKind::Ipv4));
+
+    let input = "user[at]example[dot]com";
+    let link = finder.links(input).next().unwrap();
+    let components = link.components();
+    assert_eq!(components.local_part().map(|r| &input[r]), Some("user"));
+    assert_eq!(
+        components.host().map(|r| &input[r]),
+        Some("example[dot]com")
+    );
+}
+
+#[cfg(feature = "homograph")]
+#[test]
+fn host_analysis_of_defanged_link() {
+    let mut finder = LinkFinder::new();
+    finder.defanged(true);
+    let input = "hxxps://\u{430}pple[.]com";
+    let link = finder.links(input).next().unwrap();
+    let analysis = link.host_analysis().unwrap();
+    assert_eq!(analysis.confusables(), &[(8, '\u{430}')]);
+    assert_eq!(analysis.lookalike_labels().to_vec(), vec![8..14]);
+    assert_eq!(analysis.skeleton(), "apple.corn");
+}
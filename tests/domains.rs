@@ -1,7 +1,7 @@
 mod common;
 
 use crate::common::assert_linked_with;
-use linkify::{LinkFinder, LinkKind};
+use linkify::{HostKind, LinkFinder, LinkKind};
 
 #[test]
 fn domain_valid() {
@@ -161,6 +161,77 @@ fn without_scheme_should_stop() {
     assert_not_linked("1abc://example.com");
 }
 
+#[test]
+fn ipv6() {
+    assert_linked("http://[::1]/", "|http://[::1]/|");
+    assert_linked("http://[::1]", "|http://[::1]|");
+    assert_linked(
+        "http://[2001:db8::1]:8080/path",
+        "|http://[2001:db8::1]:8080/path|",
+    );
+    assert_linked(
+        "(http://[2001:db8:0:0:0:0:0:1]/)",
+        "(|http://[2001:db8:0:0:0:0:0:1]/|)",
+    );
+    assert_linked("http://[::ffff:192.0.2.1]/", "|http://[::ffff:192.0.2.1]/|");
+    assert_linked("http://[::1]:8080.", "|http://[::1]:8080|.");
+    assert_linked("http://[::1]:", "|http://[::1]|:");
+    assert_linked(
+        "https://user:pass@[::1]:443/",
+        "|https://user:pass@[::1]:443/|",
+    );
+    assert_linked("foo://[::1]/", "|foo://[::1]/|");
+}
+
+#[test]
+fn ipv6_zone_id() {
+    assert_linked("http://[fe80::1%25eth0]/", "|http://[fe80::1%25eth0]/|");
+    assert_linked("http://[fe80::1%eth0]/", "|http://[fe80::1%eth0]/|");
+    assert_not_linked("http://[fe80::1%]/");
+}
+
+#[test]
+fn ipv_future() {
+    assert_linked("http://[v1.fe80::a+en1]/", "|http://[v1.fe80::a+en1]/|");
+    assert_not_linked("http://[v1.]/");
+    assert_not_linked("http://[vz.a]/");
+}
+
+#[test]
+fn ipv6_invalid() {
+    assert_not_linked("http://[]/");
+    assert_not_linked("http://[::1/");
+    assert_not_linked("http://[1:2:3:4:5:6:7]/");
+    assert_not_linked("http://[1:2:3:4:5:6:7:8:9]/");
+    assert_not_linked("http://[1::2::3]/");
+    assert_not_linked("http://[12345::1]/");
+    assert_not_linked("http://[::g]/");
+    assert_not_linked("http://[::1.2.3]/");
+    assert_not_linked("http://[::1 ]/");
+    assert_linked("http://example.com[::1]/", "|http://example.com|[::1]/");
+    // Address literals are not supported in emails
+    assert_not_linked("foo@[::1]");
+}
+
+#[test]
+fn host_kind() {
+    let finder = LinkFinder::new();
+    let host_kind = |s: &str| finder.links(s).next().unwrap().host_kind();
+
+    assert_eq!(host_kind("http://example.com/"), Some(HostKind::Domain));
+    assert_eq!(host_kind("http://localhost:8080"), Some(HostKind::Domain));
+    assert_eq!(host_kind("http://127.0.0.1/"), Some(HostKind::Ipv4));
+    assert_eq!(host_kind("http://[::1]:8080/"), Some(HostKind::Ipv6));
+    assert_eq!(
+        host_kind("http://user@[fe80::1%25eth0]/"),
+        Some(HostKind::Ipv6)
+    );
+    assert_eq!(host_kind("http://[v1.fe80::a]/"), Some(HostKind::IpvFuture));
+    assert_eq!(host_kind("facetime://+19995551234"), Some(HostKind::Other));
+    assert_eq!(host_kind("file:///home"), None);
+    assert_eq!(host_kind("foo@example.com"), Some(HostKind::Domain));
+}
+
 #[test]
 pub fn test_international_not_allowed() {
     let mut finder = LinkFinder::new();
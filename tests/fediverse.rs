@@ -0,0 +1,139 @@
+mod common;
+
+use crate::common::assert_linked_with;
+use linkify::html::{href, HtmlRenderer};
+use linkify::{LinkFinder, LinkKind};
+
+#[test]
+fn not_found_by_default() {
+    let finder = LinkFinder::new();
+    assert_linked_with(&finder, "@alice@mastodon.social", "@alice@mastodon.social");
+}
+
+#[test]
+fn handles() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "@alice@mastodon.social",
+        "|@alice@mastodon.social|",
+    );
+    assert_linked_with(
+        &finder,
+        "Follow @john.doe@fosstodon.org!",
+        "Follow |@john.doe@fosstodon.org|!",
+    );
+    assert_linked_with(
+        &finder,
+        "(@bob_1@social.example.com.)",
+        "(|@bob_1@social.example.com|.)",
+    );
+    assert_linked_with(
+        &finder,
+        "@Alice@Mastodon.Social",
+        "|@Alice@Mastodon.Social|",
+    );
+}
+
+#[test]
+fn not_handles() {
+    let finder = finder();
+    // Instances need to be domain names
+    assert_linked_with(&finder, "@alice@localhost", "@alice@localhost");
+    assert_linked_with(&finder, "@alice@[::1]", "@alice@[::1]");
+    assert_linked_with(&finder, "@alice@", "@alice@");
+    assert_linked_with(&finder, "@@mastodon.social", "@@mastodon.social");
+    // Usernames can't end with `.` or `-`
+    assert_linked_with(
+        &finder,
+        "@alice.@mastodon.social",
+        "@alice.@mastodon.social",
+    );
+    // Not after words or paths
+    assert_linked_with(
+        &finder,
+        "a@alice@mastodon.social",
+        "a@alice@mastodon.social",
+    );
+    assert_linked_with(
+        &finder,
+        "example.org/@alice@mastodon.social",
+        "example.org/@alice@mastodon.social",
+    );
+}
+
+#[test]
+fn handles_and_emails() {
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[LinkKind::Email, LinkKind::FediverseHandle]);
+    assert_linked_with(
+        &finder,
+        "alice@example.com and @alice@mastodon.social",
+        "|alice@example.com| and |@alice@mastodon.social|",
+    );
+    let kinds: Vec<_> = finder
+        .links("alice@example.com @alice@mastodon.social")
+        .map(|link| *link.kind())
+        .collect();
+    assert_eq!(kinds, vec![LinkKind::Email, LinkKind::FediverseHandle]);
+}
+
+#[test]
+fn handles_and_mentions() {
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[LinkKind::FediverseHandle, LinkKind::Mention]);
+    let links: Vec<_> = finder
+        .links("@alice@mastodon.social and @bob")
+        .map(|link| (link.as_str().to_string(), *link.kind()))
+        .collect();
+    assert_eq!(
+        links,
+        vec![
+            (
+                "@alice@mastodon.social".to_string(),
+                LinkKind::FediverseHandle
+            ),
+            ("@bob".to_string(), LinkKind::Mention),
+        ]
+    );
+}
+
+#[test]
+fn components() {
+    let finder = finder();
+    let text = "Hi @alice@mastodon.social";
+    let link = finder.links(text).next().unwrap();
+    let components = link.components();
+    assert_eq!(&text[components.local_part().unwrap()], "alice");
+    assert_eq!(&text[components.host().unwrap()], "mastodon.social");
+    assert_eq!(components.scheme(), None);
+}
+
+#[test]
+fn tld_validator() {
+    let mut finder = finder();
+    finder.tld_validator(|tld| tld == "social");
+    assert_linked_with(
+        &finder,
+        "@alice@mastodon.social",
+        "|@alice@mastodon.social|",
+    );
+    assert_linked_with(&finder, "@alice@example.invalid", "@alice@example.invalid");
+}
+
+#[test]
+fn html() {
+    let finder = finder();
+    let link = finder.links("@alice@mastodon.social").next().unwrap();
+    assert_eq!(href(&link, "https"), "https://mastodon.social/@alice");
+    assert_eq!(
+        HtmlRenderer::new().render(finder.spans("Hi @alice@mastodon.social")),
+        "Hi <a href=\"https://mastodon.social/@alice\">@alice@mastodon.social</a>"
+    );
+}
+
+fn finder() -> LinkFinder {
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[LinkKind::FediverseHandle]);
+    finder
+}
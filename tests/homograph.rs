@@ -0,0 +1,138 @@
+#![cfg(feature = "homograph")]
+
+use linkify::{HostAnalysis, LinkFinder};
+
+#[test]
+fn ascii_host() {
+    let analysis = analyze("https://apple.com/path");
+    assert!(!analysis.is_suspicious());
+    assert!(analysis.confusables().is_empty());
+    assert_eq!(analysis.skeleton(), "apple.corn");
+    assert_eq!(analysis.punycode(), "apple.com");
+}
+
+#[test]
+fn mixed_script() {
+    // Cyrillic `а` and Latin `pple`
+    let input = "https://\u{430}pple.com";
+    let analysis = analyze(input);
+    assert!(analysis.is_suspicious());
+    assert_eq!(analysis.mixed_script_labels().to_vec(), vec![8..14]);
+    assert_eq!(&input[8..14], "\u{430}pple");
+    assert_eq!(analysis.confusables(), &[(8, '\u{430}')]);
+    assert_eq!(analysis.skeleton(), analyze("https://apple.com").skeleton());
+    assert_eq!(analysis.punycode(), "xn--pple-43d.com");
+}
+
+#[test]
+fn whole_script_lookalike() {
+    // All Cyrillic, but looks like `appie`
+    let analysis = analyze("https://\u{430}\u{440}\u{440}\u{4cf}\u{435}.com");
+    assert!(analysis.is_suspicious());
+    assert!(analysis.mixed_script_labels().is_empty());
+    assert_eq!(analysis.lookalike_labels().to_vec(), vec![8..18]);
+    assert_eq!(analysis.confusables().len(), 5);
+    assert_eq!(analysis.skeleton(), analyze("https://appie.com").skeleton());
+    assert_eq!(analysis.punycode(), "xn--80ak6aa92e.com");
+}
+
+#[test]
+fn not_suspicious() {
+    let analysis = analyze("http://пример.com");
+    assert!(!analysis.is_suspicious());
+    assert!(!analysis.confusables().is_empty());
+    assert_eq!(analysis.punycode(), "xn--e1afmkfd.com");
+
+    let analysis = analyze("https://münchen.de");
+    assert!(!analysis.is_suspicious());
+    assert_eq!(analysis.punycode(), "xn--mnchen-3ya.de");
+
+    // Japanese can mix scripts
+    let analysis = analyze("https://日本語ドメイン.jp");
+    assert!(!analysis.is_suspicious());
+}
+
+#[test]
+fn invisible_chars() {
+    let input = "https://pay\u{200b}pal.com";
+    let analysis = analyze(input);
+    assert!(analysis.is_suspicious());
+    assert_eq!(analysis.invisible_chars(), &[(11, '\u{200b}')]);
+    assert_eq!(analysis.skeleton(), "paypal.corn");
+
+    let analysis = analyze("https://example\u{202e}moc.com");
+    assert_eq!(analysis.invisible_chars(), &[(15, '\u{202e}')]);
+}
+
+#[test]
+fn greek_and_full_width() {
+    let analysis = analyze("https://g\u{3bf}\u{3bf}gle.com");
+    assert!(analysis.is_suspicious());
+    assert_eq!(analysis.skeleton(), "google.corn");
+
+    let analysis = analyze("https://\u{ff47}oogle.com");
+    assert_eq!(analysis.confusables(), &[(8, '\u{ff47}')]);
+    assert_eq!(analysis.skeleton(), "google.corn");
+}
+
+#[test]
+fn uppercase() {
+    let analysis = analyze("https://\u{410}PPLE.com");
+    assert!(analysis.is_suspicious());
+    assert_eq!(analysis.skeleton(), "apple.corn");
+    assert_eq!(analysis.punycode(), "xn--pple-43d.com");
+
+    let analysis = analyze("https://\u{410}pple.COM");
+    assert_eq!(analysis.punycode(), "xn--pple-43d.com");
+    let analysis = analyze("https://Example.COM");
+    assert_eq!(analysis.punycode(), "example.com");
+}
+
+#[test]
+fn punycode_with_idna_mapping() {
+    let analysis = analyze("https://\u{ff47}oogle.com");
+    assert_eq!(analysis.punycode(), "google.com");
+    let analysis = analyze("https://fa\u{df}.de");
+    assert_eq!(analysis.punycode(), "xn--fa-hia.de");
+}
+
+#[test]
+fn punycode_labels_decoded() {
+    let input = "https://xn--pple-43d.com";
+    let analysis = analyze(input);
+    assert!(analysis.is_suspicious());
+    assert_eq!(analysis.mixed_script_labels().to_vec(), vec![8..20]);
+    assert_eq!(analysis.confusables(), &[(8, '\u{430}')]);
+    assert_eq!(analysis.skeleton(), analyze("https://apple.com").skeleton());
+    assert_eq!(analysis.punycode(), "xn--pple-43d.com");
+
+    let analysis = analyze("https://XN--80AK6AA92E.com");
+    assert_eq!(analysis.lookalike_labels().to_vec(), vec![8..22]);
+
+    let analysis = analyze("https://xn--mnchen-3ya.de");
+    assert!(!analysis.is_suspicious());
+}
+
+#[test]
+fn emails() {
+    let input = "user@\u{430}pple.com";
+    let finder = LinkFinder::new();
+    let link = finder.links(input).next().unwrap();
+    let analysis = link.host_analysis().unwrap();
+    assert!(analysis.is_suspicious());
+    assert_eq!(analysis.confusables(), &[(5, '\u{430}')]);
+}
+
+#[test]
+fn no_domain() {
+    let finder = LinkFinder::new();
+    let link = finder.links("http://127.0.0.1/").next().unwrap();
+    assert_eq!(link.host_analysis(), None);
+}
+
+fn analyze(input: &str) -> HostAnalysis {
+    let finder = LinkFinder::new();
+    let link = finder.links(input).next().unwrap();
+    assert_eq!(link.as_str(), input, "whole input should be a link");
+    link.host_analysis().unwrap()
+}
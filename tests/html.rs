@@ -0,0 +1,141 @@
+use linkify::html::{href, HtmlRenderer, LinkAction};
+use linkify::{LinkFinder, LinkKind};
+
+#[test]
+fn plain_text_is_escaped() {
+    let html = render(&HtmlRenderer::new(), "<b>\"Tom\" & 'Jerry'</b>");
+    assert_eq!(
+        html,
+        "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;"
+    );
+}
+
+#[test]
+fn links() {
+    let html = render(
+        &HtmlRenderer::new(),
+        "See http://example.org/?a=1&b=2, or foo@example.org.",
+    );
+    assert_eq!(
+        html,
+        "See <a href=\"http://example.org/?a=1&amp;b=2\">http://example.org/?a=1&amp;b=2</a>, \
+         or <a href=\"mailto:foo@example.org\">foo@example.org</a>."
+    );
+}
+
+#[test]
+fn without_scheme() {
+    let mut renderer = HtmlRenderer::new();
+    assert_eq!(
+        render(&renderer, "example.org:8080/foo"),
+        "<a href=\"https://example.org:8080/foo\">example.org:8080/foo</a>"
+    );
+
+    renderer.default_scheme("http");
+    assert_eq!(
+        render(&renderer, "example.org"),
+        "<a href=\"http://example.org\">example.org</a>"
+    );
+}
+
+#[test]
+fn attributes() {
+    let mut renderer = HtmlRenderer::new();
+    renderer
+        .rel("nofollow")
+        .target("_blank")
+        .class(LinkKind::Url, "url")
+        .class(LinkKind::Email, "email")
+        .attribute(Some(LinkKind::Url), "title", "\"URL\"")
+        .rel("nofollow noopener");
+
+    assert_eq!(
+        render(&renderer, "http://example.org foo@example.org"),
+        "<a href=\"http://example.org\" rel=\"nofollow noopener\" target=\"_blank\" class=\"url\" \
+         title=\"&quot;URL&quot;\">http://example.org</a> \
+         <a href=\"mailto:foo@example.org\" rel=\"nofollow noopener\" target=\"_blank\" \
+         class=\"email\">foo@example.org</a>"
+    );
+}
+
+#[test]
+fn filter() {
+    let mut renderer = HtmlRenderer::new();
+    renderer.filter(|link| {
+        if link.as_str().contains("evil") {
+            LinkAction::Text
+        } else if link.kind() == &LinkKind::Email {
+            LinkAction::Href(format!("/contact?to={}", link.as_str()))
+        } else {
+            LinkAction::Link
+        }
+    });
+
+    assert_eq!(
+        render(
+            &renderer,
+            "http://evil.example <http://example.org> foo@example.org"
+        ),
+        "http://evil.example &lt;<a href=\"http://example.org\">http://example.org</a>&gt; \
+         <a href=\"/contact?to=foo@example.org\">foo@example.org</a>"
+    );
+}
+
+#[test]
+fn script_schemes_are_not_linked() {
+    let html = render(
+        &HtmlRenderer::new(),
+        "javascript://x/%0Aalert(1) VBScript://x/y data://text/html,x http://example.org",
+    );
+    assert_eq!(
+        html,
+        "javascript://x/%0Aalert(1) VBScript://x/y data://text/html,x \
+         <a href=\"http://example.org\">http://example.org</a>"
+    );
+
+    // Unless the filter explicitly provides the href
+    let mut renderer = HtmlRenderer::new();
+    renderer.filter(|link| LinkAction::Href(link.as_str().to_string()));
+    assert_eq!(
+        render(&renderer, "javascript://x/%0Aalert(1)"),
+        "<a href=\"javascript://x/%0Aalert(1)\">javascript://x/%0Aalert(1)</a>"
+    );
+}
+
+#[test]
+fn render_to() {
+    let finder = LinkFinder::new();
+    let mut html = String::from("<p>");
+    HtmlRenderer::new()
+        .render_to(finder.spans("http://example.org"), &mut html)
+        .unwrap();
+    html.push_str("</p>");
+    assert_eq!(
+        html,
+        "<p><a href=\"http://example.org\">http://example.org</a></p>"
+    );
+}
+
+#[test]
+fn href_of_link() {
+    let mut finder = LinkFinder::new();
+    finder.url_must_have_scheme(false);
+    let hrefs: Vec<_> = finder
+        .links("example.org http://example.org foo@example.org")
+        .map(|link| href(&link, "https"))
+        .collect();
+    assert_eq!(
+        hrefs,
+        vec![
+            "https://example.org",
+            "http://example.org",
+            "mailto:foo@example.org"
+        ]
+    );
+}
+
+fn render(renderer: &HtmlRenderer, input: &str) -> String {
+    let mut finder = LinkFinder::new();
+    finder.url_must_have_scheme(false);
+    renderer.render(finder.spans(input))
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use crate::common::assert_linked_with;
+use linkify::{InputFormat, LinkFinder, LinkKind};
+
+#[test]
+fn text_content() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "<p class=\"x\">See http://example.org/ or <b>foo@example.org</b></p>",
+        "<p class=\"x\">See |http://example.org/| or <b>|foo@example.org|</b></p>",
+    );
+}
+
+#[test]
+fn tags_end_links() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "http://example.org/<br>path",
+        "|http://example.org/|<br>path",
+    );
+}
+
+#[test]
+fn attributes() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "<img src=\"http://example.org/a.png\" alt='http://example.com'> <div data-x=http://example.net>",
+        "<img src=\"http://example.org/a.png\" alt='http://example.com'> <div data-x=http://example.net>",
+    );
+    // A `>` in a quoted attribute value doesn't end the tag
+    assert_linked_with(
+        &finder,
+        "<span title=\"a > http://example.org\">http://example.com</span>",
+        "<span title=\"a > http://example.org\">|http://example.com|</span>",
+    );
+}
+
+#[test]
+fn skipped_elements() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "<a href=\"http://example.org\">http://example.org</a> http://example.com",
+        "<a href=\"http://example.org\">http://example.org</a> |http://example.com|",
+    );
+    assert_linked_with(
+        &finder,
+        "<pre><code>curl http://example.org</code></pre><CODE>http://example.com</CODE>",
+        "<pre><code>curl http://example.org</code></pre><CODE>http://example.com</CODE>",
+    );
+    assert_linked_with(
+        &finder,
+        "<script>var u = \"<a>http://example.org\";</script><style>a { background: url(http://example.com) }</style>",
+        "<script>var u = \"<a>http://example.org\";</script><style>a { background: url(http://example.com) }</style>",
+    );
+    assert_linked_with(
+        &finder,
+        "<!-- http://example.org --><!DOCTYPE html> http://example.com",
+        "<!-- http://example.org --><!DOCTYPE html> |http://example.com|",
+    );
+}
+
+#[test]
+fn entities() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "http://example.org/?a=1&amp;b=2 and &lt;http://example.com&gt;",
+        "|http://example.org/?a=1&amp;b=2| and &lt;|http://example.com|&gt;",
+    );
+    assert_linked_with(
+        &finder,
+        "http://example.org/&#x2F;a&#47;b&#32;c &unknown;",
+        "|http://example.org/&#x2F;a&#47;b|&#32;c &unknown;",
+    );
+
+    let input = "&quot;http://example.org/&auml;&quot;";
+    let link = finder.links(input).next().unwrap();
+    assert_eq!(link.as_str(), "http://example.org/&auml");
+    assert_eq!((link.start(), link.end()), (6, 30));
+}
+
+#[test]
+fn not_tags() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "1 < 2 http://example.org <3",
+        "1 < 2 |http://example.org| <3",
+    );
+}
+
+#[test]
+fn many_unterminated_tags() {
+    // Each of these used to scan to the end of the input, which took minutes
+    let finder = finder();
+    for tag in &[
+        "<a x",
+        "<a href='",
+        "<a x=\"'<b y='\" ",
+        "<script></SCRIPT>",
+    ] {
+        let text = format!("{} http://example.org", tag.repeat(50_000));
+        let links: Vec<_> = finder.links(&text).map(|link| link.as_str()).collect();
+        assert_eq!(links, vec!["http://example.org"], "{}", tag);
+    }
+}
+
+#[test]
+fn existing_links() {
+    let mut finder = finder();
+    finder.existing_links(true);
+    let input = "<a href='mailto:foo@example.org'>mail</a> <A HREF=http://example.org>x</A> \
+                 http://example.com <a name=top></a>";
+    let links: Vec<_> = finder
+        .links(input)
+        .map(|link| (link.as_str(), *link.kind(), link.is_existing()))
+        .collect();
+    assert_eq!(
+        links,
+        vec![
+            ("mailto:foo@example.org", LinkKind::Url, true),
+            ("http://example.org", LinkKind::Url, true),
+            ("http://example.com", LinkKind::Url, false),
+        ]
+    );
+}
+
+fn finder() -> LinkFinder {
+    let mut finder = LinkFinder::new();
+    finder.input_format(InputFormat::Html);
+    finder
+}
@@ -0,0 +1,94 @@
+use linkify::{LineIndex, LinkFinder, Position};
+
+#[test]
+fn empty() {
+    let index = LineIndex::new("");
+    assert_eq!(index.line_count(), 1);
+    assert_eq!(index.position(0), Position::new(0, 0));
+    assert_eq!(index.position_utf16(0), Position::new(0, 0));
+    assert_eq!(index.char_offset(0), 0);
+    assert_eq!(index.utf16_offset(0), 0);
+}
+
+#[test]
+fn lines() {
+    let text = "a\nbc\r\nd\re\n";
+    let index = LineIndex::new(text);
+    assert_eq!(index.line_count(), 5);
+    assert_eq!(index.position(0), Position::new(0, 0));
+    assert_eq!(index.position(1), Position::new(0, 1));
+    assert_eq!(index.position(2), Position::new(1, 0));
+    assert_eq!(index.position(4), Position::new(1, 2));
+    assert_eq!(index.position(5), Position::new(1, 3));
+    assert_eq!(index.position(6), Position::new(2, 0));
+    assert_eq!(index.position(8), Position::new(3, 0));
+    assert_eq!(index.position(10), Position::new(4, 0));
+}
+
+#[test]
+fn wide_chars() {
+    // `é` is 2 bytes, `日` 3 bytes and `🦀` 4 bytes (2 UTF-16 code units)
+    let text = "é日\n🦀x";
+    let index = LineIndex::new(text);
+    assert_eq!(index.char_offset(2), 1);
+    assert_eq!(index.char_offset(5), 2);
+    assert_eq!(index.char_offset(6), 3);
+    assert_eq!(index.char_offset(10), 4);
+    assert_eq!(index.char_offset(11), 5);
+    assert_eq!(index.utf16_offset(10), 5);
+    assert_eq!(index.utf16_offset(11), 6);
+    assert_eq!(index.position(10), Position::new(1, 4));
+    assert_eq!(index.position_utf16(10), Position::new(1, 2));
+    assert_eq!(index.position_utf16(11), Position::new(1, 3));
+}
+
+#[test]
+fn links() {
+    let text = "Über\n\n  (https://example.org/ä) and 🦀 a@example.org\r\nhttp://b.org";
+    let index = LineIndex::new(text);
+    let finder = LinkFinder::new();
+    let ranges: Vec<_> = finder
+        .links(text)
+        .map(|link| index.range_utf16(link.start()..link.end()))
+        .collect();
+    assert_eq!(
+        ranges,
+        vec![
+            Position::new(2, 3)..Position::new(2, 24),
+            Position::new(2, 33)..Position::new(2, 46),
+            Position::new(3, 0)..Position::new(3, 12),
+        ]
+    );
+
+    let link = finder.links(text).nth(1).unwrap();
+    assert_eq!(
+        index.range(link.start()..link.end()),
+        Position::new(2, 36)..Position::new(2, 49)
+    );
+    let chars: Vec<char> = text.chars().collect();
+    let start = index.char_offset(link.start());
+    let end = index.char_offset(link.end());
+    assert_eq!(chars[start..end].iter().collect::<String>(), link.as_str());
+}
+
+#[test]
+fn spans() {
+    let text = "日本 http://example.org 語";
+    let index = LineIndex::new(text);
+    let finder = LinkFinder::new();
+    let utf16: Vec<u16> = text.encode_utf16().collect();
+    for span in finder.spans(text) {
+        let start = index.utf16_offset(span.start());
+        let end = index.utf16_offset(span.end());
+        assert_eq!(
+            String::from_utf16(&utf16[start..end]).unwrap(),
+            span.as_str()
+        );
+    }
+}
+
+#[test]
+#[should_panic]
+fn out_of_bounds() {
+    LineIndex::new("abc").position(4);
+}
@@ -0,0 +1,266 @@
+#![cfg(feature = "lsp")]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use serde_json::{json, Value};
+
+#[test]
+fn document_links() {
+    let mut server = Server::start();
+    let result = server.request(1, "initialize", json!({"capabilities": {}}));
+    assert_eq!(result["capabilities"]["textDocumentSync"], 1);
+    assert_eq!(
+        result["capabilities"]["documentLinkProvider"]["resolveProvider"],
+        false
+    );
+    server.notify("initialized", json!({}));
+
+    server.open(
+        "file:///a.md",
+        "Über 🦀 https://example.org/ä\nmail a@example.org",
+    );
+    let links = server.document_links(2, "file:///a.md");
+    assert_eq!(
+        links,
+        json!([
+            {
+                "range": {"start": {"line": 0, "character": 8}, "end": {"line": 0, "character": 29}},
+                "target": "https://example.org/ä",
+            },
+            {
+                "range": {"start": {"line": 1, "character": 5}, "end": {"line": 1, "character": 18}},
+                "target": "mailto:a@example.org",
+            },
+        ])
+    );
+
+    server.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": {"uri": "file:///a.md", "version": 2},
+            "contentChanges": [{"text": "http://b.org"}],
+        }),
+    );
+    let links = server.document_links(3, "file:///a.md");
+    assert_eq!(links[0]["target"], "http://b.org");
+    assert_eq!(links.as_array().unwrap().len(), 1);
+
+    server.notify(
+        "textDocument/didClose",
+        json!({"textDocument": {"uri": "file:///a.md"}}),
+    );
+    assert_eq!(server.document_links(4, "file:///a.md"), json!([]));
+
+    server.shutdown(5);
+}
+
+#[test]
+fn options() {
+    let mut server = Server::start();
+    server.request(
+        1,
+        "initialize",
+        json!({
+            "capabilities": {},
+            "initializationOptions": {"kinds": ["url", "mention"], "url_must_have_scheme": false},
+        }),
+    );
+    server.open("file:///a.txt", "@alice: example.org, a@example.org");
+    let links = server.document_links(2, "file:///a.txt");
+    // Mentions don't have a target
+    assert_eq!(links.as_array().unwrap().len(), 1);
+    assert_eq!(links[0]["target"], "https://example.org");
+
+    server.notify(
+        "workspace/didChangeConfiguration",
+        json!({"settings": {"linkify": {"kinds": ["email", "reference"], "reference_rules": {
+            "repository": ["robinst", "linkify"],
+            "issue_url": "https://github.com/{owner}/{repo}/issues/{n}",
+        }}}}),
+    );
+    server.open("file:///b.txt", "a@example.org fixes #42");
+    let links = server.document_links(3, "file:///b.txt");
+    assert_eq!(links[0]["target"], "mailto:a@example.org");
+    assert_eq!(
+        links[1]["target"],
+        "https://github.com/robinst/linkify/issues/42"
+    );
+
+    server.shutdown(4);
+}
+
+#[test]
+fn errors() {
+    let mut server = Server::start();
+    let response = server.call(1, "textDocument/documentLink", json!({}));
+    assert_eq!(response["error"]["code"], -32002);
+
+    server.request(2, "initialize", json!({"capabilities": {}}));
+    let response = server.call(3, "textDocument/hover", json!({}));
+    assert_eq!(response["error"]["code"], -32601);
+    let response = server.call(4, "textDocument/documentLink", json!({}));
+    assert_eq!(response["error"]["code"], -32602);
+
+    // Exit without shutdown
+    server.notify("exit", Value::Null);
+    assert_eq!(server.child.wait().unwrap().code(), Some(1));
+}
+
+#[test]
+fn requests_after_shutdown() {
+    let mut server = Server::start();
+    server.request(1, "initialize", json!({"capabilities": {}}));
+    assert_eq!(server.request(2, "shutdown", Value::Null), Value::Null);
+    let response = server.call(
+        3,
+        "textDocument/documentLink",
+        json!({"textDocument": {"uri": "file:///a.md"}}),
+    );
+    assert_eq!(response["error"]["code"], -32600);
+    server.notify("exit", Value::Null);
+    assert!(server.child.wait().unwrap().success());
+}
+
+#[test]
+fn content_too_large() {
+    let mut server = Server::start();
+    server.request(1, "initialize", json!({"capabilities": {}}));
+
+    // More than the maximum of 64 MiB, the content is skipped without reading it into memory
+    let length = 64 * 1024 * 1024 + 1;
+    write!(server.stdin, "Content-Length: {}\r\n\r\n", length).unwrap();
+    let chunk = vec![b' '; 1024 * 1024];
+    for _ in 0..64 {
+        server.stdin.write_all(&chunk).unwrap();
+    }
+    server.stdin.write_all(b" ").unwrap();
+    let response = server.receive();
+    assert_eq!(response["id"], Value::Null);
+    assert_eq!(response["error"]["code"], -32700);
+
+    // Messages after it are still read
+    server.open("file:///a.txt", "http://a.org");
+    let links = server.document_links(2, "file:///a.txt");
+    assert_eq!(links[0]["target"], "http://a.org");
+
+    server.shutdown(3);
+}
+
+#[test]
+fn header_too_long() {
+    let mut server = Server::start();
+    server.request(1, "initialize", json!({"capabilities": {}}));
+
+    // A header line without a line break is not read into memory at once
+    let content = json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}).to_string();
+    write!(server.stdin, "X-Padding: {}\r\n", "x".repeat(1024 * 1024)).unwrap();
+    write!(
+        server.stdin,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )
+    .unwrap();
+    let response = server.receive();
+    assert_eq!(response["id"], Value::Null);
+    assert_eq!(response["error"]["code"], -32700);
+
+    // Messages after it are still read
+    server.open("file:///a.txt", "http://a.org");
+    let links = server.document_links(2, "file:///a.txt");
+    assert_eq!(links[0]["target"], "http://a.org");
+
+    server.shutdown(3);
+}
+
+struct Server {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Server {
+    fn start() -> Server {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_linkify-lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Server {
+            child,
+            stdin,
+            stdout,
+        }
+    }
+
+    fn open(&mut self, uri: &str, text: &str) {
+        self.notify(
+            "textDocument/didOpen",
+            json!({"textDocument": {"uri": uri, "languageId": "markdown", "version": 1, "text": text}}),
+        );
+    }
+
+    fn document_links(&mut self, id: u64, uri: &str) -> Value {
+        self.request(
+            id,
+            "textDocument/documentLink",
+            json!({"textDocument": {"uri": uri}}),
+        )
+    }
+
+    fn shutdown(mut self, id: u64) {
+        assert_eq!(self.request(id, "shutdown", Value::Null), Value::Null);
+        self.notify("exit", Value::Null);
+        assert!(self.child.wait().unwrap().success());
+    }
+
+    fn request(&mut self, id: u64, method: &str, params: Value) -> Value {
+        let response = self.call(id, method, params);
+        assert_eq!(response["error"], Value::Null, "{}", response);
+        response["result"].clone()
+    }
+
+    fn call(&mut self, id: u64, method: &str, params: Value) -> Value {
+        self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}));
+        let response = self.receive();
+        assert_eq!(response["id"], id);
+        response
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({"jsonrpc": "2.0", "method": method, "params": params}));
+    }
+
+    fn send(&mut self, message: Value) {
+        let content = message.to_string();
+        write!(
+            self.stdin,
+            "Content-Length: {}\r\n\r\n{}",
+            content.len(),
+            content
+        )
+        .unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            self.stdout.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(value) = line.strip_prefix("Content-Length: ") {
+                length = value.parse().unwrap();
+            }
+        }
+        let mut content = vec![0; length];
+        self.stdout.read_exact(&mut content).unwrap();
+        serde_json::from_slice(&content).unwrap()
+    }
+}
@@ -0,0 +1,211 @@
+#![cfg(feature = "markdown")]
+
+mod common;
+
+use crate::common::assert_linked_with;
+use linkify::{InputFormat, LinkFinder, LinkKind};
+
+#[test]
+fn prose() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "# See http://example.org\n\n* and foo@example.org\n",
+        "# See |http://example.org|\n\n* and |foo@example.org|\n",
+    );
+}
+
+#[test]
+fn code_spans() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "Not `http://example.org` or ``a ` http://example.com``, but http://example.net",
+        "Not `http://example.org` or ``a ` http://example.com``, but |http://example.net|",
+    );
+    // Unclosed backticks are text
+    assert_linked_with(&finder, "`http://example.org", "`|http://example.org|");
+    // Escaped backticks too
+    assert_linked_with(
+        &finder,
+        "\\`http://example.org`",
+        "\\`|http://example.org|`",
+    );
+}
+
+#[test]
+fn fenced_code_blocks() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "a\n```sh\ncurl http://example.org\n```\nhttp://example.com\n",
+        "a\n```sh\ncurl http://example.org\n```\n|http://example.com|\n",
+    );
+    assert_linked_with(
+        &finder,
+        "~~~~\nhttp://example.org\n~~~\nhttp://example.com\n~~~~\nhttp://example.net",
+        "~~~~\nhttp://example.org\n~~~\nhttp://example.com\n~~~~\n|http://example.net|",
+    );
+    // Unclosed fences go until the end
+    assert_linked_with(
+        &finder,
+        "```\nhttp://example.org",
+        "```\nhttp://example.org",
+    );
+}
+
+#[test]
+fn indented_code_blocks() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "Text:\n\n    http://example.org\n\nhttp://example.com",
+        "Text:\n\n    http://example.org\n\n|http://example.com|",
+    );
+    // Paragraph continuation
+    assert_linked_with(
+        &finder,
+        "Text\n    http://example.org",
+        "Text\n    |http://example.org|",
+    );
+    // List item continuation
+    assert_linked_with(
+        &finder,
+        "- item\n\n    http://example.org",
+        "- item\n\n    |http://example.org|",
+    );
+}
+
+#[test]
+fn inline_links() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "[http://example.org](http://example.com) http://example.net",
+        "[http://example.org](http://example.com) |http://example.net|",
+    );
+    assert_linked_with(
+        &finder,
+        "![logo](http://example.org/logo.png \"http://example.com\")",
+        "![logo](http://example.org/logo.png \"http://example.com\")",
+    );
+    assert_linked_with(
+        &finder,
+        "[a](<http://example.org/a b>) and [b](http://example.org/(b))",
+        "[a](<http://example.org/a b>) and [b](http://example.org/(b))",
+    );
+    // Not a link, just brackets
+    assert_linked_with(
+        &finder,
+        "[see http://example.org] (http://example.com)",
+        "[see |http://example.org|] (|http://example.com|)",
+    );
+}
+
+#[test]
+fn reference_links() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "[http://example.org][ref], [Ref][] and [REF]\n\n[ref]: http://example.com \"Title\"\n",
+        "[http://example.org][ref], [Ref][] and [REF]\n\n[ref]: http://example.com \"Title\"\n",
+    );
+    // Undefined labels are just text
+    assert_linked_with(
+        &finder,
+        "[http://example.org][missing]",
+        "[|http://example.org|][missing]",
+    );
+}
+
+#[test]
+fn autolinks_and_html() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "<http://example.org> <foo@example.org> <!-- http://example.com --> http://example.net",
+        "<http://example.org> <foo@example.org> <!-- http://example.com --> |http://example.net|",
+    );
+    assert_linked_with(
+        &finder,
+        "<a href=\"http://example.org\">http://example.org</a> <img src=\"http://example.com\">",
+        "<a href=\"http://example.org\">http://example.org</a> <img src=\"http://example.com\">",
+    );
+    assert_linked_with(
+        &finder,
+        "<code>http://example.org</code> <b>http://example.com</b>",
+        "<code>http://example.org</code> <b>|http://example.com|</b>",
+    );
+}
+
+#[test]
+fn existing_links() {
+    let mut finder = finder();
+    finder.existing_links(true);
+    let input = "[a](http://example.org) <foo@example.org> http://example.com `http://a.b`\n\n\
+                 [b]: /relative\n";
+    let links: Vec<_> = finder
+        .links(input)
+        .map(|link| (link.as_str(), *link.kind(), link.is_existing()))
+        .collect();
+    assert_eq!(
+        links,
+        vec![
+            ("http://example.org", LinkKind::Url, true),
+            ("foo@example.org", LinkKind::Email, true),
+            ("http://example.com", LinkKind::Url, false),
+            ("/relative", LinkKind::Url, true),
+        ]
+    );
+
+    let spans: Vec<_> = finder
+        .spans(input)
+        .filter(|span| span.is_existing())
+        .map(|span| span.as_str())
+        .collect();
+    assert_eq!(
+        spans,
+        vec!["http://example.org", "foo@example.org", "/relative"]
+    );
+
+    // Existing links are only returned for the enabled kinds
+    finder.kinds(&[LinkKind::Url]);
+    assert_linked_with(&finder, "<foo@example.org>", "<foo@example.org>");
+}
+
+#[test]
+fn unterminated_destination_with_backslash() {
+    let finder = finder();
+    assert_linked_with(&finder, "[a](b\\", "[a](b\\");
+    assert_linked_with(&finder, "[a](http://x.org\\", "[a](|http://x.org|\\");
+    assert_linked_with(&finder, "[a](<b\\", "[a](<b\\");
+}
+
+#[test]
+fn many_unclosed_openers() {
+    // Each of these used to scan to the end of the input, which took minutes
+    let finder = finder();
+    for opener in &["[", "[a](", "[a](x (", "<a>", "<a x", "<!--"] {
+        let text = format!("{} http://example.org", opener.repeat(50_000));
+        let links: Vec<_> = finder.links(&text).map(|link| link.as_str()).collect();
+        assert_eq!(links, vec!["http://example.org"], "{}", opener);
+    }
+}
+
+#[test]
+fn plain_is_default() {
+    let mut finder = LinkFinder::new();
+    assert_linked_with(&finder, "`http://example.org`", "`|http://example.org|`");
+    finder.input_format(InputFormat::Plain);
+    assert_linked_with(
+        &finder,
+        "[a](http://example.org)",
+        "[a](|http://example.org|)",
+    );
+}
+
+fn finder() -> LinkFinder {
+    let mut finder = LinkFinder::new();
+    finder.input_format(InputFormat::Markdown);
+    finder
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use crate::common::assert_linked_with;
+use linkify::{LinkFinder, LinkKind, OpaqueSyntax, SchemePolicy};
+
+#[test]
+fn not_registered() {
+    let finder = LinkFinder::new();
+    assert_linked_with(
+        &finder,
+        "mailto:foo@example.com?subject=hi",
+        "mailto:|foo@example.com|?subject=hi",
+    );
+    assert_linked_with(&finder, "urn:isbn:0451450523", "urn:isbn:0451450523");
+    assert_linked_with(&finder, "Note: this", "Note: this");
+}
+
+#[test]
+fn mailto() {
+    assert_linked("mailto:foo@example.com", "|mailto:foo@example.com|");
+    assert_linked(
+        "mailto:foo@example.com?subject=hi.",
+        "|mailto:foo@example.com?subject=hi|.",
+    );
+    assert_linked(
+        "mailto:a@example.com,b@example.org",
+        "|mailto:a@example.com,b@example.org|",
+    );
+    assert_linked("mailto:a@example.com, b", "|mailto:a@example.com|, b");
+    assert_linked("(mailto:a@example.com)", "(|mailto:a@example.com|)");
+    assert_not_linked("mailto:foo");
+    assert_not_linked("mailto:.foo@example.com");
+    assert_not_linked("mailto:@example.com");
+}
+
+#[test]
+fn tel() {
+    assert_linked("tel:+1-555-0100", "|tel:+1-555-0100|");
+    assert_linked("Call tel:+1-555-0100.", "Call |tel:+1-555-0100|.");
+    assert_linked("tel:(555)0100;ext=12", "|tel:(555)0100;ext=12|");
+    assert_linked("tel:911;", "|tel:911|;");
+    assert_linked("sms:+15550100", "|sms:+15550100|");
+    assert_not_linked("tel:12");
+    assert_not_linked("tel:+");
+    assert_not_linked("tel:abc");
+}
+
+#[test]
+fn urn() {
+    assert_linked("urn:isbn:0451450523", "|urn:isbn:0451450523|");
+    assert_linked("See urn:ietf:rfc:2648.", "See |urn:ietf:rfc:2648|.");
+    assert_linked("\"urn:isbn:0451450523\"", "\"|urn:isbn:0451450523|\"");
+    assert_not_linked("urn:x:foo");
+    assert_not_linked("urn:-isbn:0451450523");
+    assert_not_linked("urn:isbn-:0451450523");
+    assert_not_linked("urn:isbn:");
+    assert_not_linked("urn:isbn");
+}
+
+#[test]
+fn magnet() {
+    assert_linked(
+        "magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a&dn=foo",
+        "|magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a&dn=foo|",
+    );
+    assert_not_linked("magnet:?");
+    assert_not_linked("magnet:xt");
+}
+
+#[test]
+fn xmpp() {
+    assert_linked("xmpp:user@host", "|xmpp:user@host|");
+    assert_linked(
+        "xmpp:user@example.org/phone?message.",
+        "|xmpp:user@example.org/phone?message|.",
+    );
+    assert_linked("xmpp:example.org", "|xmpp:example.org|");
+    assert_not_linked("xmpp:@example.org");
+    assert_not_linked("xmpp:a..b");
+}
+
+#[test]
+fn data() {
+    assert_linked(
+        "data:text/plain;base64,SGVsbG8=",
+        "|data:text/plain;base64,SGVsbG8=|",
+    );
+    assert_linked("data:,Hello%2C%20World!", "|data:,Hello%2C%20World|!");
+    assert_not_linked("data:text/plain");
+    assert_not_linked("data:,");
+}
+
+#[test]
+fn path() {
+    let mut policy = SchemePolicy::new();
+    policy.opaque("news", OpaqueSyntax::Path);
+    let finder = finder(policy);
+
+    assert_linked_with(
+        &finder,
+        "Read news:comp.lang.rust.",
+        "Read |news:comp.lang.rust|.",
+    );
+    assert_linked_with(&finder, "news:/foo", "news:/foo");
+    assert_linked_with(&finder, "news:", "news:");
+}
+
+#[test]
+fn kind_is_url() {
+    let finder = finder(common_policy());
+    let links: Vec<_> = finder.links("mailto:foo@example.com").collect();
+    assert_eq!(1, links.len());
+    assert_eq!(&LinkKind::Url, links[0].kind());
+}
+
+#[test]
+fn with_emails() {
+    let finder = finder(common_policy());
+    assert_linked_with(
+        &finder,
+        "foo@example.com or mailto:bar@example.com",
+        "|foo@example.com| or |mailto:bar@example.com|",
+    );
+}
+
+#[test]
+fn denied() {
+    let mut policy = common_policy();
+    policy.deny(&["data"]);
+    let finder = finder(policy);
+
+    assert_linked_with(&finder, "data:,Hello", "data:,Hello");
+    assert_linked_with(&finder, "urn:isbn:0451450523", "|urn:isbn:0451450523|");
+}
+
+#[test]
+fn with_authority() {
+    // Registering a scheme as opaque doesn't change how it's parsed when it has an authority
+    let mut policy = SchemePolicy::new();
+    policy.opaque("https", OpaqueSyntax::Path);
+    let finder = finder(policy);
+
+    assert_linked_with(&finder, "https://example.org/", "|https://example.org/|");
+    assert_linked_with(&finder, "https:foo", "|https:foo|");
+}
+
+fn common_policy() -> SchemePolicy {
+    let mut policy = SchemePolicy::new();
+    policy.common_opaque_schemes();
+    policy
+}
+
+fn finder(policy: SchemePolicy) -> LinkFinder {
+    let mut finder = LinkFinder::new();
+    finder.scheme_policy(policy);
+    finder
+}
+
+fn assert_not_linked(s: &str) {
+    assert_linked(s, s);
+}
+
+fn assert_linked(input: &str, expected: &str) {
+    let mut finder = finder(common_policy());
+    finder.kinds(&[LinkKind::Url]);
+    assert_linked_with(&finder, input, expected);
+}
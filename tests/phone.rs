@@ -0,0 +1,109 @@
+mod common;
+
+use crate::common::assert_linked_with;
+use linkify::{LinkFinder, LinkKind};
+
+#[test]
+fn not_found_by_default() {
+    let finder = LinkFinder::new();
+    assert_linked_with(&finder, "Call +41 44 668 18 00", "Call +41 44 668 18 00");
+}
+
+#[test]
+fn international() {
+    let finder = finder();
+    assert_linked_with(&finder, "+41 44 668 18 00", "|+41 44 668 18 00|");
+    assert_linked_with(&finder, "Call +14155552671.", "Call |+14155552671|.");
+    assert_linked_with(&finder, "(+1-555-010-0199)", "(|+1-555-010-0199|)");
+    assert_linked_with(&finder, "+44 (0)20 7946 0958", "|+44 (0)20 7946 0958|");
+    assert_linked_with(&finder, "+1 (555) 010-0199", "|+1 (555) 010-0199|");
+    // Too short or too long
+    assert_linked_with(&finder, "+41 44", "+41 44");
+    assert_linked_with(&finder, "+1234 5678 9012 3456", "+1234 5678 9012 3456");
+    // Country codes don't start with 0
+    assert_linked_with(&finder, "+0 555 010 0199", "+0 555 010 0199");
+}
+
+#[test]
+fn national() {
+    let finder = finder();
+    assert_linked_with(&finder, "(555) 010-0199", "|(555) 010-0199|");
+    assert_linked_with(&finder, "555-010-0199", "|555-010-0199|");
+    assert_linked_with(&finder, "555.010.0199", "|555.010.0199|");
+    assert_linked_with(&finder, "020 7946 0958", "|020 7946 0958|");
+    assert_linked_with(&finder, "tel. 044 668 18 00!", "tel. |044 668 18 00|!");
+    // Separators are not included at the end
+    assert_linked_with(&finder, "555-010-0199- ", "|555-010-0199|- ");
+}
+
+#[test]
+fn not_phone_numbers() {
+    let finder = finder();
+    // No separators
+    assert_linked_with(&finder, "id 5550100199", "id 5550100199");
+    // IPv4 address
+    assert_linked_with(&finder, "192.168.100.200", "192.168.100.200");
+    // Dates
+    assert_linked_with(&finder, "2024-01-31", "2024-01-31");
+    assert_linked_with(&finder, "31.01.2024", "31.01.2024");
+    // Decimal numbers and thousands separators
+    assert_linked_with(&finder, "3.14159265", "3.14159265");
+    assert_linked_with(&finder, "1 000 000", "1 000 000");
+    // Part of other words or numbers
+    assert_linked_with(&finder, "abc555-010-0199", "abc555-010-0199");
+    assert_linked_with(&finder, "555-010-0199abc", "555-010-0199abc");
+    assert_linked_with(&finder, "v1.555-010-0199", "v1.555-010-0199");
+    assert_linked_with(&finder, "555--010--0199", "555--010--0199");
+}
+
+#[test]
+fn with_other_links() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "http://example.org/555-010-0199 555-010-0199@example.org or 555-010-0199",
+        "|http://example.org/555-010-0199| |555-010-0199@example.org| or |555-010-0199|",
+    );
+
+    let mut finder = finder;
+    finder.url_must_have_scheme(false);
+    assert_linked_with(
+        &finder,
+        "example.org, 10.0.0.1 and +41 44 668 18 00",
+        "|example.org|, |10.0.0.1| and |+41 44 668 18 00|",
+    );
+}
+
+#[test]
+fn phone_number() {
+    let finder = finder();
+    let numbers: Vec<_> = finder
+        .links("+44 (0)20 7946 0958, (555) 010-0199, http://example.org")
+        .map(|link| link.phone_number())
+        .collect();
+    assert_eq!(
+        numbers,
+        vec![
+            Some("+442079460958".to_string()),
+            Some("5550100199".to_string()),
+            None
+        ]
+    );
+}
+
+#[test]
+fn only_phone() {
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[LinkKind::Phone]);
+    assert_linked_with(
+        &finder,
+        "http://example.org +41 44 668 18 00",
+        "http://example.org |+41 44 668 18 00|",
+    );
+}
+
+fn finder() -> LinkFinder {
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[LinkKind::Url, LinkKind::Email, LinkKind::Phone]);
+    finder
+}
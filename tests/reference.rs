@@ -0,0 +1,214 @@
+mod common;
+
+use crate::common::assert_linked_with;
+use linkify::html::HtmlRenderer;
+use linkify::{LinkFinder, LinkKind, Reference, ReferenceRules};
+
+#[test]
+fn not_found_by_default() {
+    let finder = LinkFinder::new();
+    assert_linked_with(&finder, "#123 GH-1 3fcb00f", "#123 GH-1 3fcb00f");
+}
+
+#[test]
+fn issues() {
+    let finder = finder();
+    assert_linked_with(&finder, "#123", "|#123|");
+    assert_linked_with(&finder, "Fixes #1, #22.", "Fixes |#1|, |#22|.");
+    assert_linked_with(&finder, "(robinst/linkify#42)", "(|robinst/linkify#42|)");
+    assert_linked_with(&finder, "my-org/my.repo#7", "|my-org/my.repo#7|");
+    assert_linked_with(&finder, "See GH-123", "See |GH-123|");
+    // Not after words, entities or paths
+    assert_linked_with(&finder, "C#1", "C#1");
+    assert_linked_with(&finder, "&#123;", "&#123;");
+    assert_linked_with(&finder, "example.org/#1", "example.org/#1");
+    assert_linked_with(&finder, "a/b/c#1", "a/b/c#1");
+    assert_linked_with(&finder, "#12ab #", "#12ab #");
+    assert_linked_with(&finder, "XGH-123", "XGH-123");
+    // Numbers that don't fit into a u64
+    assert_linked_with(
+        &finder,
+        "#18446744073709551615 #18446744073709551616 GH-99999999999999999999",
+        "|#18446744073709551615| #18446744073709551616 GH-99999999999999999999",
+    );
+}
+
+#[test]
+fn tickets() {
+    let mut finder = finder();
+    assert_linked_with(&finder, "PROJ-1234", "PROJ-1234");
+    finder.reference_rules(ReferenceRules::new().projects(&["PROJ", "AB2"]));
+    assert_linked_with(&finder, "PROJ-1234 and AB2-5", "|PROJ-1234| and |AB2-5|");
+    assert_linked_with(&finder, "UTF-8 XPROJ-1 PROJ-", "UTF-8 XPROJ-1 PROJ-");
+    assert_linked_with(
+        &finder,
+        "PROJ-99999999999999999999",
+        "PROJ-99999999999999999999",
+    );
+}
+
+#[test]
+fn commits() {
+    let finder = finder();
+    assert_linked_with(&finder, "In 3fcb00f.", "In |3fcb00f|.");
+    assert_linked_with(
+        &finder,
+        "d60f4013f2a6b3c9e8d7f1a2b3c4d5e6f7a8b9c0",
+        "|d60f4013f2a6b3c9e8d7f1a2b3c4d5e6f7a8b9c0|",
+    );
+    // Too short or too long
+    assert_linked_with(&finder, "3fcb00", "3fcb00");
+    assert_linked_with(
+        &finder,
+        "d60f4013f2a6b3c9e8d7f1a2b3c4d5e6f7a8b9c0a",
+        "d60f4013f2a6b3c9e8d7f1a2b3c4d5e6f7a8b9c0a",
+    );
+    // Only digits, uppercase, or part of a word
+    assert_linked_with(&finder, "1234567 3FCB00F", "1234567 3FCB00F");
+    assert_linked_with(
+        &finder,
+        "x3fcb00f 3fcb00fx v1.3fcb00f",
+        "x3fcb00f 3fcb00fx v1.3fcb00f",
+    );
+}
+
+#[test]
+fn disabled_rules() {
+    let mut finder = finder();
+    finder.reference_rules(ReferenceRules::new().issues(false).commits(false));
+    assert_linked_with(&finder, "#1 GH-1 3fcb00f", "#1 GH-1 3fcb00f");
+}
+
+#[test]
+fn with_other_kinds() {
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[
+        LinkKind::Url,
+        LinkKind::Hashtag,
+        LinkKind::Phone,
+        LinkKind::Reference,
+    ]);
+    let links: Vec<_> = finder
+        .links("#1 #rust +41 44 668 18 00 https://example.org/#2 3fcb00f")
+        .map(|link| (link.as_str().to_string(), *link.kind()))
+        .collect();
+    assert_eq!(
+        links,
+        vec![
+            ("#1".to_string(), LinkKind::Reference),
+            ("#rust".to_string(), LinkKind::Hashtag),
+            ("+41 44 668 18 00".to_string(), LinkKind::Phone),
+            ("https://example.org/#2".to_string(), LinkKind::Url),
+            ("3fcb00f".to_string(), LinkKind::Reference),
+        ]
+    );
+}
+
+#[test]
+fn parts() {
+    let mut finder = finder();
+    finder.reference_rules(ReferenceRules::new().projects(&["PROJ"]));
+    let references: Vec<_> = finder
+        .links("#1 owner/repo#2 GH-3 PROJ-4 3fcb00f")
+        .map(|link| link.reference().unwrap())
+        .collect();
+    assert_eq!(
+        references,
+        vec![
+            Reference::Issue {
+                owner: None,
+                repo: None,
+                number: 1
+            },
+            Reference::Issue {
+                owner: Some("owner"),
+                repo: Some("repo"),
+                number: 2
+            },
+            Reference::Issue {
+                owner: None,
+                repo: None,
+                number: 3
+            },
+            Reference::Ticket {
+                project: "PROJ",
+                number: 4
+            },
+            Reference::Commit { sha: "3fcb00f" },
+        ]
+    );
+
+    let url = LinkFinder::new()
+        .links("http://example.org")
+        .next()
+        .unwrap();
+    assert_eq!(url.reference(), None);
+}
+
+#[test]
+fn urls() {
+    let mut finder = finder();
+    finder.reference_rules(
+        ReferenceRules::new()
+            .projects(&["PROJ"])
+            .issue_url("https://github.com/{owner}/{repo}/issues/{n}")
+            .ticket_url("https://jira.example.com/browse/{project}-{n}")
+            .commit_url("https://github.com/{owner}/{repo}/commit/{sha}"),
+    );
+    let urls: Vec<_> = finder
+        .links("#1 owner/repo#2 PROJ-3 3fcb00f")
+        .map(|link| link.reference_url())
+        .collect();
+    assert_eq!(
+        urls,
+        vec![
+            // No default repository
+            None,
+            Some("https://github.com/owner/repo/issues/2".to_string()),
+            Some("https://jira.example.com/browse/PROJ-3".to_string()),
+            None,
+        ]
+    );
+
+    let rules = ReferenceRules::new()
+        .repository("robinst", "linkify")
+        .commit_url("https://github.com/{owner}/{repo}/commit/{sha}");
+    assert_eq!(
+        rules.url(&Reference::Commit { sha: "3fcb00f" }),
+        Some("https://github.com/robinst/linkify/commit/3fcb00f".to_string())
+    );
+    assert_eq!(
+        rules.url(&Reference::Issue {
+            owner: None,
+            repo: None,
+            number: 1
+        }),
+        None
+    );
+}
+
+#[test]
+fn html() {
+    let mut finder = finder();
+    finder.reference_rules(
+        ReferenceRules::new()
+            .repository("robinst", "linkify")
+            .issue_url("https://github.com/{owner}/{repo}/issues/{n}"),
+    );
+    let spans: Vec<_> = finder.spans("Fixes #42").collect();
+    assert_eq!(spans[0].reference_url(), None);
+    assert_eq!(
+        spans[1].reference_url(),
+        Some("https://github.com/robinst/linkify/issues/42".to_string())
+    );
+    assert_eq!(
+        HtmlRenderer::new().render(finder.spans("Fixes #42 in 3fcb00f")),
+        "Fixes <a href=\"https://github.com/robinst/linkify/issues/42\">#42</a> in 3fcb00f"
+    );
+}
+
+fn finder() -> LinkFinder {
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[LinkKind::Reference]);
+    finder
+}
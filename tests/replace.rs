@@ -0,0 +1,89 @@
+use std::borrow::Cow;
+
+use linkify::{InputFormat, LinkFinder, LinkKind};
+
+#[test]
+fn replace_all() {
+    let finder = LinkFinder::new();
+    let result = finder.replace_all("a http://example.org b foo@example.org c", |link| {
+        Cow::Owned(format!("<{}>", link.as_str()))
+    });
+    assert_eq!(result, "a <http://example.org> b <foo@example.org> c");
+    assert!(matches!(result, Cow::Owned(_)));
+}
+
+#[test]
+fn replace_all_whole_text() {
+    let finder = LinkFinder::new();
+    let result = finder.replace_all("http://example.org", |_| Cow::Borrowed("x"));
+    assert_eq!(result, "x");
+    let result = finder.replace_all("http://example.org", |_| Cow::Borrowed(""));
+    assert_eq!(result, "");
+}
+
+#[test]
+fn replace_all_keep_some() {
+    let finder = LinkFinder::new();
+    let result = finder.replace_all(
+        "http://a.org, foo@example.org, http://b.org",
+        |link| match link.kind() {
+            LinkKind::Email => Cow::Borrowed("(email)"),
+            _ => Cow::Borrowed(link.as_str()),
+        },
+    );
+    assert_eq!(result, "http://a.org, (email), http://b.org");
+}
+
+#[test]
+fn replace_all_unchanged_borrows() {
+    let finder = LinkFinder::new();
+    let text = "http://example.org and foo@example.org";
+    let result = finder.replace_all(text, |link| Cow::Borrowed(link.as_str()));
+    assert!(matches!(result, Cow::Borrowed(s) if s.as_ptr() == text.as_ptr()));
+
+    // An owned replacement that's equal to the link doesn't count as a change either
+    let result = finder.replace_all(text, |link| Cow::Owned(link.as_str().to_string()));
+    assert!(matches!(result, Cow::Borrowed(_)));
+
+    let result = finder.replace_all("no links", |_| Cow::Borrowed("x"));
+    assert!(matches!(result, Cow::Borrowed("no links")));
+}
+
+#[test]
+fn replace_all_stateful() {
+    let finder = LinkFinder::new();
+    let mut urls = Vec::new();
+    let result = finder.replace_all("See http://a.org and http://b.org.", |link| {
+        urls.push(link.as_str().to_string());
+        Cow::Owned(format!("[{}]", urls.len()))
+    });
+    assert_eq!(result, "See [1] and [2].");
+    assert_eq!(urls, vec!["http://a.org", "http://b.org"]);
+}
+
+#[test]
+fn replace_all_html_input() {
+    let mut finder = LinkFinder::new();
+    finder.input_format(InputFormat::Html);
+    // The link is decoded for finding, but replaced in the input with the entity
+    let result = finder.replace_all("<p>http://a.org/?x=1&amp;y=2 <b>", |_| Cow::Borrowed("URL"));
+    assert_eq!(result, "<p>URL <b>");
+}
+
+#[test]
+fn replace_all_to() {
+    let finder = LinkFinder::new();
+    let mut out = String::from("> ");
+    finder
+        .replace_all_to("a http://example.org b", &mut out, |link| {
+            Cow::Owned(link.as_str().to_uppercase())
+        })
+        .unwrap();
+    assert_eq!(out, "> a HTTP://EXAMPLE.ORG b");
+
+    let mut out = String::new();
+    finder
+        .replace_all_to("no links", &mut out, |_| Cow::Borrowed("x"))
+        .unwrap();
+    assert_eq!(out, "no links");
+}
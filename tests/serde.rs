@@ -0,0 +1,238 @@
+#![cfg(feature = "serde")]
+
+use linkify::{
+    InputFormat, LinkFinder, LinkFinderOptions, LinkKind, OpaqueSyntax, OwnedLink, SchemePolicy,
+    SchemeRules, TagRules,
+};
+use serde_json::json;
+
+#[test]
+fn serialize_link() {
+    let finder = LinkFinder::new();
+    let link = finder.links("See http://example.org/a").next().unwrap();
+    assert_eq!(
+        serde_json::to_value(&link).unwrap(),
+        json!({
+            "text": "http://example.org/a",
+            "start": 4,
+            "end": 24,
+            "kind": "url",
+            "existing": false,
+            "defanged": false,
+            "components": {
+                "scheme": {"start": 4, "end": 8},
+                "host": {"start": 11, "end": 22},
+                "path": {"start": 22, "end": 24},
+            },
+        })
+    );
+}
+
+#[test]
+fn serialize_email() {
+    let finder = LinkFinder::new();
+    let link = finder.links("a@example.org").next().unwrap();
+    let value = serde_json::to_value(&link).unwrap();
+    assert_eq!(value["kind"], "email");
+    assert_eq!(
+        value["components"],
+        json!({
+            "host": {"start": 2, "end": 13},
+            "local_part": {"start": 0, "end": 1},
+        })
+    );
+}
+
+#[test]
+fn serialize_spans() {
+    let finder = LinkFinder::new();
+    let spans: Vec<_> = finder.spans("Go to http://example.org!").collect();
+    assert_eq!(
+        serde_json::to_value(&spans[0]).unwrap(),
+        json!({
+            "text": "Go to ",
+            "start": 0,
+            "end": 6,
+            "kind": null,
+            "existing": false,
+            "defanged": false,
+            "components": null,
+        })
+    );
+    assert_eq!(serde_json::to_value(&spans[1]).unwrap()["kind"], "url");
+    assert_eq!(serde_json::to_value(&spans[2]).unwrap()["text"], "!");
+}
+
+#[test]
+fn owned_link_round_trip() {
+    let finder = LinkFinder::new();
+    let link: OwnedLink = finder
+        .links("foo https://example.org:8080/x")
+        .next()
+        .unwrap()
+        .into();
+    let json = serde_json::to_string(&link).unwrap();
+    let deserialized: OwnedLink = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, link);
+    assert_eq!(deserialized.components(), link.components());
+
+    let mut finder = LinkFinder::new();
+    finder.defanged(true);
+    let link: OwnedLink = finder.links("hxxp://a[.]org").next().unwrap().into();
+    let json = serde_json::to_string(&link).unwrap();
+    let deserialized: OwnedLink = serde_json::from_str(&json).unwrap();
+    assert!(deserialized.is_defanged());
+    assert_eq!(deserialized.refanged(), "http://a.org");
+}
+
+#[test]
+fn owned_link_without_components() {
+    let link: OwnedLink =
+        serde_json::from_str(r#"{"text": "example.org/a", "start": 2, "end": 15, "kind": "url"}"#)
+            .unwrap();
+    assert_eq!(link.as_str(), "example.org/a");
+    assert_eq!(link.kind(), &LinkKind::Url);
+    assert!(!link.is_existing());
+    assert_eq!(link.components().scheme(), None);
+    assert_eq!(link.components().host(), Some(2..13));
+}
+
+#[test]
+fn owned_link_invalid_end() {
+    let result: Result<OwnedLink, _> = serde_json::from_str(
+        r#"{"text": "http://example.org", "start": 0, "end": 5, "kind": "url"}"#,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn options_from_toml() {
+    let options: LinkFinderOptions = toml::from_str(
+        r#"
+        kinds = ["url", "email", "mention"]
+        url_must_have_scheme = false
+        email_domain_must_have_dot = false
+        input_format = "html"
+
+        [mention_rules]
+        chars = "_"
+        max_length = 15
+        unicode = false
+        require_letter = false
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        options.kinds,
+        vec![LinkKind::Url, LinkKind::Email, LinkKind::Mention]
+    );
+    assert!(!options.url_must_have_scheme);
+    assert!(!options.email_domain_must_have_dot);
+    assert!(options.url_can_be_iri);
+    assert_eq!(options.input_format, InputFormat::Html);
+    assert_eq!(
+        options.mention_rules,
+        TagRules::mention().chars("_").max_length(15)
+    );
+    assert_eq!(options.hashtag_rules, TagRules::hashtag());
+
+    let finder = LinkFinder::from(options.clone());
+    assert_eq!(finder.options(), options);
+    let links: Vec<_> = finder
+        .links("<p>@bob: example.org, root@localhost</p>")
+        .collect();
+    assert_eq!(links.len(), 3);
+}
+
+#[test]
+fn options_defaults() {
+    let options: LinkFinderOptions = toml::from_str("").unwrap();
+    assert_eq!(options, LinkFinder::new().options());
+    assert_eq!(options.kinds, vec![LinkKind::Url, LinkKind::Email]);
+
+    let options: LinkFinderOptions =
+        serde_json::from_str(r#"{"reference_rules": {"repository": ["robinst", "linkify"]}}"#)
+            .unwrap();
+    let mut finder = LinkFinder::from(options.clone());
+    finder.kinds(&[LinkKind::Reference]);
+    let link = finder.links("Fixes #42").next().unwrap();
+    assert_eq!(link.as_str(), "#42");
+    assert_eq!(finder.options().reference_rules, options.reference_rules);
+}
+
+#[test]
+fn options_round_trip() {
+    let mut finder = LinkFinder::new();
+    finder
+        .kinds(&[LinkKind::Url, LinkKind::Hashtag])
+        .defanged(true)
+        .existing_links(true);
+    let mut policy = SchemePolicy::new();
+    policy
+        .scheme("gopher", SchemeRules::host())
+        .opaque("urn", OpaqueSyntax::Urn)
+        .deny(&["javascript"]);
+    finder.scheme_policy(policy);
+    let json = serde_json::to_string(&finder.options()).unwrap();
+    let options: LinkFinderOptions = serde_json::from_str(&json).unwrap();
+    assert_eq!(options, finder.options());
+
+    let toml = toml::to_string(&finder.options()).unwrap();
+    let options: LinkFinderOptions = toml::from_str(&toml).unwrap();
+    assert_eq!(options, finder.options());
+}
+
+#[test]
+fn scheme_policy_from_toml() {
+    let options: LinkFinderOptions = toml::from_str(
+        r#"
+        [scheme_policy]
+        allowed = ["https", "urn"]
+        denied = ["javascript"]
+
+        [scheme_policy.rules.https]
+        require_host = true
+        userinfo_allowed = false
+
+        [scheme_policy.opaque]
+        urn = "urn"
+        "#,
+    )
+    .unwrap();
+    let policy = &options.scheme_policy;
+    assert!(policy.is_allowed("HTTPS"));
+    assert!(!policy.is_allowed("http"));
+    assert!(!policy.is_allowed("javascript"));
+    assert!(!policy.rules("https").is_userinfo_allowed());
+    assert!(policy.rules("https").is_port_allowed());
+    assert_eq!(policy.opaque_syntax("urn"), Some(OpaqueSyntax::Urn));
+
+    let finder = LinkFinder::from(options);
+    let links: Vec<_> = finder
+        .links("http://a.org javascript://b.org https://c.org urn:isbn:0451450523")
+        .map(|link| link.as_str().to_string())
+        .collect();
+    assert_eq!(links, vec!["https://c.org", "urn:isbn:0451450523"]);
+}
+
+#[test]
+fn scheme_rules_from_toml_keep_defaults() {
+    let options: LinkFinderOptions = toml::from_str(
+        r#"
+        [scheme_policy.rules.gopher]
+        require_host = true
+        "#,
+    )
+    .unwrap();
+    let policy = &options.scheme_policy;
+    assert!(policy.rules("gopher").host_required());
+    assert!(policy.rules("http").host_required());
+    assert!(policy.rules("ssh").host_required());
+
+    let finder = LinkFinder::from(options);
+    let links: Vec<_> = finder
+        .links("http://a_b!c/x https://-foo/ gopher://example.org/1")
+        .map(|link| link.as_str().to_string())
+        .collect();
+    assert_eq!(links, vec!["gopher://example.org/1"]);
+}
@@ -0,0 +1,141 @@
+use std::io::{self, BufReader, Read};
+
+use linkify::{InputFormat, LinkFinder, LinkKind, OwnedLink};
+
+#[test]
+fn same_as_links_for_any_chunk_size() {
+    let input = "See http://example.org/a/b?c=d, (https://example.com/x_(y)) or \
+                 foo.bar@example.org.\nAnd \"http://[::1]:8080/\" and ünicode http://ä.example/ö\n";
+    let mut finder = LinkFinder::new();
+    finder.url_must_have_scheme(false);
+    let expected: Vec<_> = finder.links(input).map(OwnedLink::from).collect();
+    assert_eq!(expected.len(), 5);
+
+    for capacity in 1..input.len() + 1 {
+        let reader = BufReader::with_capacity(capacity, input.as_bytes());
+        let links: Vec<_> = finder
+            .links_reader(reader)
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(links, expected, "chunk size {}", capacity);
+    }
+}
+
+#[test]
+fn markup_input_is_scanned_as_plain_text() {
+    let input = "<a href=\"http://a.org\">http://a.org</a> `http://b.org` <b>http://c.org</b>\n";
+    let expected: Vec<_> = LinkFinder::new()
+        .links(input)
+        .map(OwnedLink::from)
+        .collect();
+    assert_eq!(expected.len(), 4);
+
+    #[allow(unused_mut)]
+    let mut formats = vec![InputFormat::Html];
+    #[cfg(feature = "markdown")]
+    formats.push(InputFormat::Markdown);
+    for format in formats {
+        let mut finder = LinkFinder::new();
+        finder.input_format(format);
+        for capacity in 1..input.len() + 1 {
+            let reader = BufReader::with_capacity(capacity, input.as_bytes());
+            let links: Vec<_> = finder
+                .links_reader(reader)
+                .collect::<io::Result<_>>()
+                .unwrap();
+            assert_eq!(links, expected, "{:?} with chunk size {}", format, capacity);
+        }
+    }
+}
+
+#[test]
+fn offsets() {
+    let input = b"\xff foo@example.org\nhttp://example.org";
+    let finder = LinkFinder::new();
+    let links: Vec<_> = finder
+        .links_reader(BufReader::with_capacity(4, &input[..]))
+        .map(|link| link.unwrap())
+        .map(|link| {
+            (
+                link.as_str().to_string(),
+                *link.kind(),
+                link.start(),
+                link.end(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        links,
+        vec![
+            ("foo@example.org".to_string(), LinkKind::Email, 2, 17),
+            ("http://example.org".to_string(), LinkKind::Url, 18, 36),
+        ]
+    );
+
+    let link = finder
+        .links_reader(BufReader::with_capacity(3, &input[..]))
+        .nth(1)
+        .unwrap()
+        .unwrap();
+    assert_eq!(link.components().host(), Some(25..36));
+}
+
+#[test]
+fn max_buffer() {
+    let input = format!("{}http://example.org", "-".repeat(100));
+    let finder = LinkFinder::new();
+
+    let mut links = finder.links_reader(BufReader::with_capacity(8, input.as_bytes()));
+    assert_eq!(
+        links.next().unwrap().unwrap().as_str(),
+        "http://example.org"
+    );
+
+    // Without whitespace, the link is split when the buffer is full
+    let mut links = finder.links_reader(BufReader::with_capacity(8, input.as_bytes()));
+    links.max_buffer(112);
+    assert_eq!(links.next().unwrap().unwrap().as_str(), "http://examp");
+    assert!(links.next().is_none());
+}
+
+#[test]
+fn read_error() {
+    struct Failing<'a>(&'a [u8]);
+
+    impl<'a> Read for Failing<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                Err(io::ErrorKind::ConnectionReset.into())
+            } else {
+                self.0.read(buf)
+            }
+        }
+    }
+
+    let finder = LinkFinder::new();
+    let mut links = finder.links_reader(BufReader::new(Failing(b"http://example.org ")));
+    assert_eq!(
+        links.next().unwrap().unwrap().as_str(),
+        "http://example.org"
+    );
+    assert!(links.next().unwrap().is_err());
+    assert!(links.next().is_none());
+}
+
+#[test]
+fn phone_numbers_with_spaces() {
+    let input = "Call +41 44 668 18 00 or\n(555) 010-0199\n";
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[LinkKind::Phone]);
+    let expected: Vec<_> = finder.links(input).map(OwnedLink::from).collect();
+    assert_eq!(expected.len(), 2);
+
+    for capacity in 1..input.len() + 1 {
+        let reader = BufReader::with_capacity(capacity, input.as_bytes());
+        let links: Vec<_> = finder
+            .links_reader(reader)
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(links, expected, "chunk size {}", capacity);
+    }
+}
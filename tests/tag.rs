@@ -0,0 +1,118 @@
+mod common;
+
+use crate::common::assert_linked_with;
+use linkify::html::{HtmlRenderer, LinkAction};
+use linkify::{LinkFinder, LinkKind, TagRules};
+
+#[test]
+fn not_found_by_default() {
+    let finder = LinkFinder::new();
+    assert_linked_with(&finder, "@alice #rust", "@alice #rust");
+}
+
+#[test]
+fn mentions() {
+    let finder = finder();
+    assert_linked_with(&finder, "@alice", "|@alice|");
+    assert_linked_with(&finder, "Hi @alice, @bob_1.", "Hi |@alice|, |@bob_1|.");
+    assert_linked_with(&finder, "(@john.doe)", "(|@john.doe|)");
+    assert_linked_with(&finder, "@some-user-", "|@some-user|-");
+    assert_linked_with(&finder, "@ alice @", "@ alice @");
+    // Not after words, paths or other tags
+    assert_linked_with(&finder, "a@alice", "a@alice");
+    assert_linked_with(&finder, "example.org/@alice", "example.org/@alice");
+    assert_linked_with(&finder, "@@alice #@alice", "@@alice #@alice");
+    // Non-ASCII letters are not allowed by default, and don't end the name
+    assert_linked_with(&finder, "@jürgen", "@jürgen");
+}
+
+#[test]
+fn mentions_and_emails() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "alice@example.com and @alice",
+        "|alice@example.com| and |@alice|",
+    );
+    let kinds: Vec<_> = finder
+        .links("alice@example.com @alice")
+        .map(|link| *link.kind())
+        .collect();
+    assert_eq!(kinds, vec![LinkKind::Email, LinkKind::Mention]);
+
+    // Followed by `@`, e.g. a Fediverse handle
+    assert_linked_with(&finder, "@alice@mastodon.social", "@alice@mastodon.social");
+
+    // With emails disabled, still not a mention
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[LinkKind::Mention]);
+    assert_linked_with(&finder, "alice@example.com", "alice@example.com");
+}
+
+#[test]
+fn hashtags() {
+    let finder = finder();
+    assert_linked_with(
+        &finder,
+        "#rust #release-notes, #日本語!",
+        "|#rust| |#release-notes|, |#日本語|!",
+    );
+    assert_linked_with(&finder, "#v1_2_3", "|#v1_2_3|");
+    // Digits only
+    assert_linked_with(&finder, "#1 #123", "#1 #123");
+    // Not after words or in entities
+    assert_linked_with(&finder, "C# &#39; a#b ##c", "C# &#39; a#b ##c");
+    // Not in URLs
+    assert_linked_with(
+        &finder,
+        "http://example.org/#fragment",
+        "|http://example.org/#fragment|",
+    );
+    // Not directly after a link, where the URL scanner stopped
+    assert_linked_with(
+        &finder,
+        "http://a.com#frag @bob.",
+        "|http://a.com|#frag |@bob|.",
+    );
+    assert_linked_with(&finder, "foo@example.org#tag", "|foo@example.org|#tag");
+}
+
+#[test]
+fn rules() {
+    let mut finder = finder();
+    finder.mention_rules(TagRules::mention().chars("_").max_length(5));
+    finder.hashtag_rules(TagRules::hashtag().unicode(false).require_letter(false));
+    assert_linked_with(&finder, "@alice @alice2", "|@alice| @alice2");
+    assert_linked_with(&finder, "@a.b", "|@a|.b");
+    assert_linked_with(&finder, "#123 #日本語", "|#123| #日本語");
+}
+
+#[test]
+fn html() {
+    let finder = finder();
+    let mut renderer = HtmlRenderer::new();
+    assert_eq!(
+        renderer.render(finder.spans("@alice #rust")),
+        "@alice #rust"
+    );
+
+    renderer.filter(|link| match link.kind() {
+        LinkKind::Mention => LinkAction::Href(format!("/users/{}", &link.as_str()[1..])),
+        _ => LinkAction::Link,
+    });
+    assert_eq!(
+        renderer.render(finder.spans("@alice #rust")),
+        "<a href=\"/users/alice\">@alice</a> #rust"
+    );
+}
+
+fn finder() -> LinkFinder {
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[
+        LinkKind::Url,
+        LinkKind::Email,
+        LinkKind::Mention,
+        LinkKind::Hashtag,
+    ]);
+    finder
+}
@@ -0,0 +1,126 @@
+use linkify::terminal::TerminalRenderer;
+use linkify::{InputFormat, LinkFinder, LinkKind, ReferenceRules};
+
+#[test]
+fn plain_text_is_unchanged() {
+    let output = render(
+        &TerminalRenderer::new(),
+        "<b>no links</b> \x1b[1mhere\x1b[0m",
+    );
+    assert_eq!(output, "<b>no links</b> \x1b[1mhere\x1b[0m");
+}
+
+#[test]
+fn links() {
+    let output = render(
+        &TerminalRenderer::new(),
+        "See http://example.org/, or foo@example.org.",
+    );
+    assert_eq!(
+        output,
+        "See \x1b]8;;http://example.org/\x1b\\http://example.org/\x1b]8;;\x1b\\, \
+         or \x1b]8;;mailto:foo@example.org\x1b\\foo@example.org\x1b]8;;\x1b\\."
+    );
+}
+
+#[test]
+fn without_scheme() {
+    let mut finder = LinkFinder::new();
+    finder.url_must_have_scheme(false);
+    let mut renderer = TerminalRenderer::new();
+    assert_eq!(
+        renderer.render(finder.spans("example.org")),
+        "\x1b]8;;https://example.org\x1b\\example.org\x1b]8;;\x1b\\"
+    );
+
+    renderer.default_scheme("http");
+    assert_eq!(
+        renderer.render(finder.spans("example.org")),
+        "\x1b]8;;http://example.org\x1b\\example.org\x1b]8;;\x1b\\"
+    );
+}
+
+#[test]
+fn uri_is_encoded() {
+    let output = render(&TerminalRenderer::new(), "http://example.org/ä?q=1");
+    assert_eq!(
+        output,
+        "\x1b]8;;http://example.org/%C3%A4?q=1\x1b\\http://example.org/ä?q=1\x1b]8;;\x1b\\"
+    );
+}
+
+#[test]
+fn ansi_input() {
+    let output = render(
+        &TerminalRenderer::new(),
+        "\x1b[32mhttp://example.org/\x1b[1mpath\x1b[0m done",
+    );
+    // The escapes are kept in the text, but not in the URI
+    assert_eq!(
+        output,
+        "\x1b[32m\x1b]8;;http://example.org/path\x1b\\http://example.org/\x1b[1mpath\x1b]8;;\x1b\\\x1b[0m done"
+    );
+}
+
+#[test]
+fn styles() {
+    let mut renderer = TerminalRenderer::new();
+    renderer
+        .style(LinkKind::Url, "4", "24")
+        .style(LinkKind::Email, "1", "22")
+        .style(LinkKind::Email, "34", "39");
+    let output = render(&renderer, "http://example.org foo@example.org");
+    assert_eq!(
+        output,
+        "\x1b]8;;http://example.org\x1b\\\x1b[4mhttp://example.org\x1b[24m\x1b]8;;\x1b\\ \
+         \x1b]8;;mailto:foo@example.org\x1b\\\x1b[34mfoo@example.org\x1b[39m\x1b]8;;\x1b\\"
+    );
+}
+
+#[test]
+fn links_without_uri() {
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[
+        LinkKind::Url,
+        LinkKind::Mention,
+        LinkKind::Hashtag,
+        LinkKind::Reference,
+    ]);
+    finder.defanged(true);
+    let mut renderer = TerminalRenderer::new();
+    renderer.style(LinkKind::Hashtag, "36", "39");
+    let output = renderer.render(finder.spans("@alice #rust #42 hxxp://example[.]org"));
+    assert_eq!(
+        output,
+        "@alice \x1b[36m#rust\x1b[39m #42 hxxp://example[.]org"
+    );
+
+    finder.reference_rules(
+        ReferenceRules::new()
+            .repository("robinst", "linkify")
+            .issue_url("https://github.com/{owner}/{repo}/issues/{n}"),
+    );
+    let output = renderer.render(finder.spans("#42"));
+    assert_eq!(
+        output,
+        "\x1b]8;;https://github.com/robinst/linkify/issues/42\x1b\\#42\x1b]8;;\x1b\\"
+    );
+}
+
+#[test]
+fn render_to() {
+    let mut output = String::from("> ");
+    TerminalRenderer::new()
+        .render_to(LinkFinder::new().spans("http://example.org"), &mut output)
+        .unwrap();
+    assert_eq!(
+        output,
+        "> \x1b]8;;http://example.org\x1b\\http://example.org\x1b]8;;\x1b\\"
+    );
+}
+
+fn render(renderer: &TerminalRenderer, input: &str) -> String {
+    let mut finder = LinkFinder::new();
+    finder.input_format(InputFormat::Ansi);
+    renderer.render(finder.spans(input))
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use crate::common::assert_linked_with;
+use linkify::LinkFinder;
+
+#[test]
+fn validator_without_scheme() {
+    let finder = finder();
+    assert_linked_with(&finder, "config.yaml", "config.yaml");
+    assert_linked_with(&finder, "example.com/foo", "|example.com/foo|");
+    assert_linked_with(&finder, "example.COM:8080", "example.COM:8080");
+    assert_linked_with(&finder, "example.com:8080", "|example.com:8080|");
+    // IPv4 addresses are not checked
+    assert_linked_with(&finder, "127.0.0.1", "|127.0.0.1|");
+}
+
+#[test]
+fn validator_not_used_with_scheme() {
+    let finder = finder();
+    assert_linked_with(&finder, "https://config.yaml", "|https://config.yaml|");
+}
+
+#[test]
+fn validator_email() {
+    let mut finder = finder();
+    assert_linked_with(&finder, "foo@example.com", "|foo@example.com|");
+    assert_linked_with(&finder, "foo@example.txt", "foo@example.txt");
+
+    // Domains without a dot are not checked
+    finder.email_domain_must_have_dot(false);
+    assert_linked_with(&finder, "root@localhost", "|root@localhost|");
+}
+
+#[cfg(feature = "tlds")]
+#[test]
+fn known_tlds() {
+    assert!(linkify::is_known_tld("com"));
+    assert!(linkify::is_known_tld("COM"));
+    assert!(linkify::is_known_tld("ch"));
+    assert!(linkify::is_known_tld("рф"));
+    assert!(linkify::is_known_tld("РФ"));
+    assert!(linkify::is_known_tld("xn--p1ai"));
+    assert!(!linkify::is_known_tld("yaml"));
+    assert!(!linkify::is_known_tld("txt"));
+    assert!(!linkify::is_known_tld(""));
+
+    let mut finder = LinkFinder::new();
+    finder.url_must_have_scheme(false);
+    finder.tld_validator(linkify::is_known_tld);
+    assert_linked_with(
+        &finder,
+        "See config.yaml, file.txt, example.org and test@example.ch",
+        "See config.yaml, file.txt, |example.org| and |test@example.ch|",
+    );
+    // Some file extensions are real TLDs
+    assert_linked_with(
+        &finder,
+        "README.md, main.rs, setup.py, files.zip",
+        "|README.md|, |main.rs|, |setup.py|, |files.zip|",
+    );
+}
+
+fn finder() -> LinkFinder {
+    let mut finder = LinkFinder::new();
+    finder.url_must_have_scheme(false);
+    finder.tld_validator(|tld| tld == "com");
+    finder
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "url")]
+
+use std::error::Error;
+
+use linkify::{LinkFinder, LinkKind, ReferenceRules, ToUrlError};
+
+#[test]
+fn urls() {
+    let finder = LinkFinder::new();
+    let link = finder
+        .links("See https://example.org/a?b#c.")
+        .next()
+        .unwrap();
+    let url = link.to_url().unwrap();
+    assert_eq!(url.as_str(), "https://example.org/a?b#c");
+    assert_eq!(url.host_str(), Some("example.org"));
+}
+
+#[test]
+fn urls_without_scheme() {
+    let mut finder = LinkFinder::new();
+    finder.url_must_have_scheme(false);
+    let link = finder.links("See example.org/a").next().unwrap();
+    assert_eq!(link.to_url().unwrap().as_str(), "https://example.org/a");
+}
+
+#[test]
+fn iri() {
+    let finder = LinkFinder::new();
+    let link = finder.links("https://bücher.de/ä").next().unwrap();
+    assert_eq!(
+        link.to_url().unwrap().as_str(),
+        "https://xn--bcher-kva.de/%C3%A4"
+    );
+}
+
+#[test]
+fn emails() {
+    let finder = LinkFinder::new();
+    let link = finder.links("Mail a@example.org").next().unwrap();
+    let url = link.to_url().unwrap();
+    assert_eq!(url.scheme(), "mailto");
+    assert_eq!(url.as_str(), "mailto:a@example.org");
+}
+
+#[test]
+fn other_kinds() {
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[
+        LinkKind::Phone,
+        LinkKind::FediverseHandle,
+        LinkKind::Mention,
+        LinkKind::Reference,
+    ]);
+    let urls: Vec<_> = finder
+        .links("+41 44 668 18 00 @alice@mastodon.social @bob #1")
+        .map(|link| link.to_url().map(|url| url.to_string()))
+        .collect();
+    assert_eq!(
+        urls,
+        vec![
+            Ok("tel:+41446681800".to_string()),
+            Ok("https://mastodon.social/@alice".to_string()),
+            Err(ToUrlError::NoUrl(LinkKind::Mention)),
+            Err(ToUrlError::NoUrl(LinkKind::Reference)),
+        ]
+    );
+
+    finder.reference_rules(
+        ReferenceRules::new()
+            .repository("robinst", "linkify")
+            .issue_url("https://github.com/{owner}/{repo}/issues/{n}"),
+    );
+    let link = finder.links("#1").next().unwrap();
+    assert_eq!(
+        link.to_url().unwrap().as_str(),
+        "https://github.com/robinst/linkify/issues/1"
+    );
+}
+
+#[test]
+fn invalid() {
+    let finder = LinkFinder::new();
+    let link = finder.links("http://example.org:99999/").next().unwrap();
+    let error = link.to_url().unwrap_err();
+    assert_eq!(error, ToUrlError::Invalid(url::ParseError::InvalidPort));
+    assert_eq!(
+        error.to_string(),
+        "link is not a valid URL: invalid port number"
+    );
+    assert!(error.source().is_some());
+
+    let error = ToUrlError::NoUrl(LinkKind::Mention);
+    assert_eq!(error.to_string(), "link of kind Mention doesn't have a URL");
+    assert!(error.source().is_none());
+}
@@ -11,6 +11,17 @@ fn send_and_sync() {
     check_sync::<Link<'_>>();
 }
 
+#[test]
+fn unwind_safe() {
+    check_unwind_safe::<LinkFinder>();
+    check_unwind_safe::<Link<'_>>();
+
+    let mut finder = LinkFinder::new();
+    finder.tld_validator(|tld| tld == "org");
+    let links = std::panic::catch_unwind(|| finder.links("http://example.org").count());
+    assert_eq!(links.unwrap(), 1);
+}
+
 #[test]
 fn equality() {
     let finder = LinkFinder::new();
@@ -27,3 +38,5 @@ fn equality() {
 fn check_send<T: Send>() {}
 
 fn check_sync<T: Sync>() {}
+
+fn check_unwind_safe<T: std::panic::UnwindSafe + std::panic::RefUnwindSafe>() {}